@@ -0,0 +1,129 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::{Target, ValidationError};
+
+///
+/// Generates an async injector. `future` holds the extra bounds of its futures and targets, and
+/// `factory` those of its factories, so the local and the `Send` injector share one implementation.
+///
+macro_rules! async_injector {
+    ($(#[$doc:meta])* $name:ident, future: [$($future:tt)*], factory: [$($factory:tt)*]) => {
+        $(#[$doc])*
+        pub struct $name<'a, T: Clone>
+        {
+            factories: Vec<Box<dyn Fn() -> Pin<Box<dyn Future<Output = T> $($future)* + 'a>> $($factory)* + 'a>>,
+            consumed: Vec<bool>,
+            targets: Vec<Box<dyn Target<T> $($future)* + 'a>>
+        }
+
+        impl<'a, T: Clone> $name<'a, T>
+        {
+            pub fn new() -> Self
+            {
+                Self {
+                    factories: Vec::new(),
+                    consumed: Vec::new(),
+                    targets: Vec::new()
+                }
+            }
+
+            ///
+            /// Registers an async factory. Its value is injected by the next call to `inject_all`.
+            ///
+            pub fn inject<F, Fut>(&mut self, factory: F) -> &mut Self
+            where
+                F: Fn() -> Fut $($factory)* + 'a,
+                Fut: Future<Output = T> $($future)* + 'a
+            {
+                self.factories.push(Box::new(move || Box::pin(factory())));
+                self.consumed.push(false);
+
+                self
+            }
+
+            ///
+            /// Registers a target. It is injected by the next call to `inject_all`.
+            ///
+            pub fn to<R: Target<T> $($future)* + 'a>(&mut self, mut target: R) -> &mut Self
+            {
+                target.describe_fields();
+                self.targets.push(Box::new(target));
+
+                self
+            }
+
+            ///
+            /// Injects the values of all registered factories into all registered targets. Every
+            /// factory is awaited once per target, and the targets are not borrowed while a factory
+            /// is pending. Once all values for a target are ready, its injected fields are cleared
+            /// and filled again, so calling this again doesn't collect values twice.
+            ///
+            pub async fn inject_all(&mut self)
+            {
+                self.consumed.fill(false);
+
+                for target in self.targets.iter_mut()
+                {
+                    let mut values = Vec::with_capacity(self.factories.len());
+
+                    for factory in self.factories.iter()
+                    {
+                        values.push(factory().await);
+                    }
+
+                    target.uninject_matching(&mut |_| true);
+
+                    for (value, consumed) in values.into_iter().zip(self.consumed.iter_mut())
+                    {
+                        *consumed |= target.inject(value);
+                    }
+                }
+            }
+
+            ///
+            /// Checks that every registered target has been fully injected, and that every factory
+            /// has been accepted by at least one target. See `Injector::validate`.
+            ///
+            pub fn validate(&self) -> Result<(), ValidationError>
+            {
+                ValidationError::check(
+                    self.targets.iter().map(|target| target.missing_injections()).enumerate(),
+                    self.consumed.iter().copied().enumerate(),
+                    &[]
+                )
+            }
+        }
+
+        impl<'a, T: Clone> Default for $name<'a, T>
+        {
+            fn default() -> Self
+            {
+                Self::new()
+            }
+        }
+    };
+}
+
+async_injector! {
+    ///
+    /// AsyncInjector is an injector for factories that produce their values asynchronously. Unlike
+    /// `Injector`, registering factories and targets doesn't inject anything yet: the values are only
+    /// produced and injected by awaiting `inject_all`. The injector doesn't depend on any particular
+    /// executor.
+    ///
+    /// Its factories, futures and targets don't need to be `Send`, so the future returned by
+    /// `inject_all` can only be run on the current thread, for instance with a local executor. Use
+    /// `SendAsyncInjector` to spawn it on a multi-threaded executor.
+    ///
+    AsyncInjector, future: [], factory: []
+}
+
+async_injector! {
+    ///
+    /// SendAsyncInjector is an `AsyncInjector` whose factories, futures and targets are `Send`, so the
+    /// future returned by `inject_all` can be spawned on a multi-threaded executor. Targets are shared
+    /// handles that can be sent to other threads, such as `Arc<Mutex<_>>` or `Arc<RwLock<_>>`.
+    ///
+    SendAsyncInjector, future: [+ Send], factory: [+ Send + Sync]
+}
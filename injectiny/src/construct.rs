@@ -0,0 +1,87 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use crate::{InjectionPoint, Keyed};
+
+///
+/// Resolve provides the values of a model to structs built with `#[derive(Construct)]`. It is
+/// implemented by `Injector`.
+///
+pub trait Resolve<T: Clone + Keyed> {
+    ///
+    /// Returns the value of the member identified by `key` that `extract` maps to `Some`, preferring
+    /// later factories as field injection does. Factories bound to another key are skipped without
+    /// being called. If no value was found, the error of the first factory that failed is returned,
+    /// or `None` if none did.
+    ///
+    fn resolve<P, F: FnMut(T) -> Option<P>>(&self, key: T::Key, extract: F) -> Result<Option<P>, Arc<dyn Error + Send + Sync>>;
+}
+
+///
+/// The error returned by a generated `construct` when some fields could not be resolved.
+///
+#[derive(Clone, Debug)]
+pub struct Missing {
+    points: Vec<InjectionPoint>,
+    failures: Vec<(InjectionPoint, Arc<dyn Error + Send + Sync>)>
+}
+
+impl Missing {
+    ///
+    /// Creates the error for the given fields, along with the errors of the factories that failed
+    /// to provide them. This is called by the code generated by `#[derive(Construct)]`.
+    ///
+    #[doc(hidden)]
+    pub fn new(points: Vec<InjectionPoint>, failures: Vec<(InjectionPoint, Arc<dyn Error + Send + Sync>)>) -> Self {
+        Self { points, failures }
+    }
+
+    ///
+    /// Returns the fields that could not be resolved.
+    ///
+    pub fn points(&self) -> &[InjectionPoint] {
+        &self.points
+    }
+
+    ///
+    /// Returns the fields that could not be resolved because a factory failed, along with its
+    /// error.
+    ///
+    pub fn failures(&self) -> &[(InjectionPoint, Arc<dyn Error + Send + Sync>)] {
+        &self.failures
+    }
+
+    fn failure(&self, point: &InjectionPoint) -> Option<&(dyn Error + Send + Sync)> {
+        self.failures.iter().find(|(failed, _)| failed == point).map(|(_, error)| &**error)
+    }
+}
+
+impl PartialEq for Missing {
+    fn eq(&self, other: &Self) -> bool {
+        // errors can't be compared, so they're considered equal if they report the same message
+        self.points == other.points && self.failures.len() == other.failures.len()
+            && self.failures.iter().zip(&other.failures).all(|((a, a_error), (b, b_error))| {
+                a == b && a_error.to_string() == b_error.to_string()
+            })
+    }
+}
+
+impl Eq for Missing {}
+
+impl Display for Missing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Construction is incomplete")?;
+
+        for point in &self.points {
+            match self.failure(point) {
+                Some(error) => write!(f, "\n  {} was not resolved: {}", point, error)?,
+                None => write!(f, "\n  {} was not resolved", point)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for Missing {}
@@ -0,0 +1,609 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::rc::{Rc, Weak};
+use std::sync::Arc;
+
+use crate::{FailedInjection, Keyed, Resolve, Target, ValidationError};
+
+///
+/// Injector is a convenience struct that can making injecting things a bit more ergonomic.
+///
+/// Factories and targets can be registered in any order, and at any time: every factory is
+/// injected into every target, regardless of which was registered first. Targets can either be
+/// borrowed (`&mut Injectee`), in which case they remain borrowed until the injector is dropped,
+/// or shared handles such as `Rc<RefCell<_>>` or `Arc<Mutex<_>>`, which can be used while they are
+/// registered.
+///
+/// Factories are only called when a target needs their value, and every factory is injected into
+/// every target. Factories registered with `inject` are never replaced: registering another
+/// factory for the same member adds it, and its value is injected after the earlier ones. To
+/// replace a factory later on, bind it to a key with `bind` instead: binding another factory to the
+/// same key replaces it, and injects the new value into every target. Keys come from the model's
+/// `Keyed` implementation, such as the `{Enum}Key` enum generated by `#[injectiny::model]`.
+///
+/// An injector can create child scopes with `child`, which inherit the factories of their parent.
+///
+pub struct Injector<'a, T: Clone>
+{
+    parent: Option<&'a Injector<'a, T>>,
+    providers: Vec<Provider<'a, T>>,
+    targets: Vec<(usize, Box<dyn Target<T> + 'a>)>,
+    failures: Vec<FailedInjection>,
+    next_factory: usize,
+    next_target: usize
+}
+
+type Factory<'a, T> = Box<dyn Fn() -> Result<T, Arc<dyn Error + Send + Sync>> + 'a>;
+
+struct Provider<'a, T: Clone>
+{
+    index: usize,
+    factory: Factory<'a, T>,
+    key: Option<Box<dyn FactoryKey>>,
+    consumed: bool
+}
+
+///
+/// The key a factory was bound to. Its type is erased, so only binding requires `T: Keyed`.
+///
+pub(crate) trait FactoryKey
+{
+    fn as_any(&self) -> &dyn Any;
+
+    fn equals(&self, other: &dyn FactoryKey) -> bool;
+}
+
+impl<K: Any + Eq> FactoryKey for K
+{
+    fn as_any(&self) -> &dyn Any
+    {
+        self
+    }
+
+    fn equals(&self, other: &dyn FactoryKey) -> bool
+    {
+        other.as_any().downcast_ref::<K>() == Some(self)
+    }
+}
+
+///
+/// The error reported when a factory bound to a key produces a value of another member.
+///
+#[derive(Debug)]
+struct WrongMember
+{
+    key: String,
+    produced: String
+}
+
+impl Display for WrongMember
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f, "factory bound to {} produced {}", self.key, self.produced)
+    }
+}
+
+impl Error for WrongMember {}
+
+///
+/// Determines how often an Injector calls a factory.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lifetime {
+    ///
+    /// The factory is called for every target it is injected into.
+    ///
+    Transient,
+
+    ///
+    /// The factory is called once, the first time its value is needed, and every target receives a
+    /// clone of that value. A failure is reported for every target.
+    ///
+    Singleton
+}
+
+///
+/// SharedInjector is an Injector that owns its factories and targets, so it can be stored for the
+/// application's lifetime.
+///
+pub type SharedInjector<T> = Injector<'static, T>;
+
+///
+/// Identifies a factory registered with `Injector::add_factory` or `Injector::bind`. Every
+/// registration gets its own handle, even if it replaces another factory.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FactoryHandle(usize);
+
+impl FactoryHandle {
+    ///
+    /// Returns the index of the factory, in the order it was registered. This is the index used in
+    /// a `ValidationError`.
+    ///
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    ///
+    /// Returns a guard that removes the factory from the injector when it is dropped.
+    ///
+    pub fn guard<'a, T: Clone>(self, injector: &Rc<RefCell<Injector<'a, T>>>) -> Registration<'a, T> {
+        Registration { injector: Rc::downgrade(injector), handle: Handle::Factory(self) }
+    }
+}
+
+///
+/// Identifies a target registered with `Injector::add_target`.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TargetHandle(usize);
+
+impl TargetHandle {
+    ///
+    /// Returns the index of the target, in the order it was registered. This is the index used in
+    /// a `ValidationError`.
+    ///
+    pub fn index(&self) -> usize {
+        self.0
+    }
+
+    ///
+    /// Returns a guard that removes the target from the injector when it is dropped.
+    ///
+    pub fn guard<'a, T: Clone>(self, injector: &Rc<RefCell<Injector<'a, T>>>) -> Registration<'a, T> {
+        Registration { injector: Rc::downgrade(injector), handle: Handle::Target(self) }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Handle {
+    Factory(FactoryHandle),
+    Target(TargetHandle)
+}
+
+///
+/// A guard that removes a factory or target from an injector shared through an `Rc<RefCell<_>>`
+/// when it is dropped. Nothing is removed if the injector has been dropped already, or if it is
+/// borrowed at that moment.
+///
+#[must_use = "the registration is removed as soon as the guard is dropped"]
+pub struct Registration<'a, T: Clone>
+{
+    injector: Weak<RefCell<Injector<'a, T>>>,
+    handle: Handle
+}
+
+impl<'a, T: Clone> Drop for Registration<'a, T>
+{
+    fn drop(&mut self)
+    {
+        let Some(injector) = self.injector.upgrade() else { return };
+        // panicking in drop could abort, so a busy injector keeps the registration
+        let Ok(mut injector) = injector.try_borrow_mut() else { return };
+
+        match self.handle {
+            Handle::Factory(handle) => injector.remove_factory(handle),
+            Handle::Target(handle) => injector.remove_target(handle)
+        };
+    }
+}
+
+impl<'a, T: Clone> Injector<'a, T>
+{
+    pub fn new() -> Self
+    {
+        Self {
+            parent: None,
+            providers: Vec::new(),
+            targets: Vec::new(),
+            failures: Vec::new(),
+            next_factory: 0,
+            next_target: 0
+        }
+    }
+
+    ///
+    /// Creates a child scope of this injector. Targets registered with the child receive the values
+    /// of its parent's factories, followed by those of the child's own factories. The parent can't
+    /// be changed while the child exists, and dropping the child releases its own targets and
+    /// factories, including its singletons.
+    ///
+    /// A factory that the child binds to a key shadows the parent's factories for that key, which
+    /// are then not called for the child's targets. Factories registered with `inject` can't be
+    /// shadowed. A failing inherited factory is reported by the child with its index in the scope
+    /// that registered it, along with how many scopes up that is.
+    ///
+    pub fn child(&self) -> Injector<'_, T>
+    {
+        Injector {
+            parent: Some(self),
+            ..Injector::new()
+        }
+    }
+
+    ///
+    /// Registers a transient factory, and injects its value into every registered target.
+    ///
+    pub fn inject<F: Fn() -> T + 'a>(&mut self, factory: F) -> &mut Self
+        where T: 'a
+    {
+        self.inject_with(Lifetime::Transient, factory)
+    }
+
+    ///
+    /// Registers a factory with the given lifetime, and injects its value into every registered
+    /// target.
+    ///
+    pub fn inject_with<F: Fn() -> T + 'a>(&mut self, lifetime: Lifetime, factory: F) -> &mut Self
+        where T: 'a
+    {
+        self.add_factory(lifetime, factory);
+        self
+    }
+
+    ///
+    /// Registers a transient fallible factory, and injects its value into every registered target.
+    /// Failures don't interrupt the wiring: they're collected for every target, and reported by
+    /// `validate`.
+    ///
+    pub fn try_inject<F, E>(&mut self, factory: F) -> &mut Self
+        where F: Fn() -> Result<T, E> + 'a, E: Into<Box<dyn Error + Send + Sync>>, T: 'a
+    {
+        self.try_inject_with(Lifetime::Transient, factory)
+    }
+
+    ///
+    /// Registers a fallible factory with the given lifetime, and injects its value into every
+    /// registered target. See `try_inject`.
+    ///
+    pub fn try_inject_with<F, E>(&mut self, lifetime: Lifetime, factory: F) -> &mut Self
+        where F: Fn() -> Result<T, E> + 'a, E: Into<Box<dyn Error + Send + Sync>>, T: 'a
+    {
+        self.try_add_factory(lifetime, factory);
+        self
+    }
+
+    ///
+    /// Registers a factory like `inject_with`, and returns a handle to remove it again.
+    ///
+    pub fn add_factory<F: Fn() -> T + 'a>(&mut self, lifetime: Lifetime, factory: F) -> FactoryHandle
+        where T: 'a
+    {
+        self.try_add_factory(lifetime, move || Ok::<_, Infallible>(factory()))
+    }
+
+    ///
+    /// Registers a fallible factory like `try_inject_with`, and returns a handle to remove it
+    /// again.
+    ///
+    pub fn try_add_factory<F, E>(&mut self, lifetime: Lifetime, factory: F) -> FactoryHandle
+        where F: Fn() -> Result<T, E> + 'a, E: Into<Box<dyn Error + Send + Sync>>, T: 'a
+    {
+        self.register(None, lifetime, factory).0
+    }
+
+    ///
+    /// Registers a factory for the member identified by `key`, and injects its value into every
+    /// registered target. A factory that was bound to the same key before is replaced. The factory
+    /// must produce values of that member; a value of another member is reported as a failure, as
+    /// with `try_inject`. Returns a handle to remove the factory again; removing it after it was
+    /// replaced does nothing.
+    ///
+    /// Fields can't tell which factory their value came from, so replacing a factory clears the
+    /// member from every target first. This keeps `collect` fields from holding on to the replaced
+    /// value, but they also lose the values of factories registered with `inject` for that member.
+    ///
+    pub fn bind<F: Fn() -> T + 'a>(&mut self, key: T::Key, lifetime: Lifetime, factory: F) -> FactoryHandle
+        where T: Keyed + 'a
+    {
+        self.try_bind(key, lifetime, move || Ok::<_, Infallible>(factory()))
+    }
+
+    ///
+    /// Registers a fallible factory for the member identified by `key`, like `bind`. Failures are
+    /// reported as with `try_inject`.
+    ///
+    pub fn try_bind<F, E>(&mut self, key: T::Key, lifetime: Lifetime, factory: F) -> FactoryHandle
+        where F: Fn() -> Result<T, E> + 'a, E: Into<Box<dyn Error + Send + Sync>>, T: Keyed + 'a
+    {
+        self.unbind(key);
+        self.register(Some(Box::new(key)), lifetime, Self::checked(key, factory)).0
+    }
+
+    ///
+    /// Binds a factory to `key` like `bind`, and returns true if it replaced a factory that was
+    /// bound to the same key.
+    ///
+    pub fn rebind<F: Fn() -> T + 'a>(&mut self, key: T::Key, lifetime: Lifetime, factory: F) -> bool
+        where T: Keyed + 'a
+    {
+        self.unbind(key);
+        self.register(Some(Box::new(key)), lifetime, Self::checked(key, move || Ok::<_, Infallible>(factory()))).1
+    }
+
+    ///
+    /// Clears the member from every target if a factory is bound to `key`, before it is replaced.
+    ///
+    fn unbind(&mut self, key: T::Key)
+        where T: Keyed
+    {
+        if !Self::binds(&self.providers, &key)
+        {
+            return;
+        }
+
+        for (_, target) in self.targets.iter_mut()
+        {
+            target.uninject_matching(&mut |value| value.key() == key);
+        }
+    }
+
+    ///
+    /// Wraps a bound factory, so values of another member than its key are reported as failures.
+    ///
+    fn checked<F, E>(key: T::Key, factory: F) -> impl Fn() -> Result<T, Box<dyn Error + Send + Sync>> + 'a
+        where F: Fn() -> Result<T, E> + 'a, E: Into<Box<dyn Error + Send + Sync>>, T: Keyed + 'a
+    {
+        move || {
+            let value = factory().map_err(Into::into)?;
+
+            match value.key() {
+                produced if produced == key => Ok(value),
+                produced => Err(Box::new(WrongMember { key: format!("{:?}", key), produced: format!("{:?}", produced) }))
+            }
+        }
+    }
+
+    ///
+    /// Removes a factory. Values it injected before stay in their targets. Returns false if the
+    /// factory was removed already.
+    ///
+    pub fn remove_factory(&mut self, handle: FactoryHandle) -> bool
+    {
+        let Some(position) = self.providers.iter().position(|provider| provider.index == handle.0) else {
+            return false;
+        };
+
+        self.providers.remove(position);
+        self.failures.retain(|failure| failure.scope != 0 || failure.factory != handle.0);
+        true
+    }
+
+    fn register<F, E>(&mut self, key: Option<Box<dyn FactoryKey>>, lifetime: Lifetime, factory: F) -> (FactoryHandle, bool)
+        where F: Fn() -> Result<T, E> + 'a, E: Into<Box<dyn Error + Send + Sync>>, T: 'a
+    {
+        let factory = move || factory().map_err(|error| Arc::from(error.into()));
+        let factory: Factory<'a, T> = match lifetime {
+            Lifetime::Transient => Box::new(factory),
+            Lifetime::Singleton => {
+                let cache = RefCell::new(None);
+                Box::new(move || cache.borrow_mut().get_or_insert_with(&factory).clone())
+            }
+        };
+
+        let replaced = key.as_deref().and_then(|key| {
+            self.providers.iter().position(|provider| provider.key.as_deref().is_some_and(|other| other.equals(key)))
+        });
+        let index = self.next_factory;
+        let mut consumed = false;
+
+        self.next_factory += 1;
+
+        // failures of a replaced factory no longer apply
+        if let Some(position) = replaced
+        {
+            let replaced = self.providers[position].index;
+            self.failures.retain(|failure| failure.scope != 0 || failure.factory != replaced);
+        }
+
+        for (target_index, target) in self.targets.iter_mut()
+        {
+            if let Some(value) = Self::produced(factory(), 0, index, *target_index, &mut self.failures)
+            {
+                consumed |= target.inject(value);
+            }
+        }
+
+        let provider = Provider { index, factory, key, consumed };
+
+        match replaced {
+            Some(position) => self.providers[position] = provider,
+            None => self.providers.push(provider)
+        }
+
+        (FactoryHandle(index), replaced.is_some())
+    }
+
+    ///
+    /// Registers a target, and injects the values of all factories inherited from parent scopes
+    /// into it, followed by those of this injector's own factories.
+    ///
+    pub fn to<R: Target<T> + 'a>(&mut self, target: R) -> &mut Self
+    {
+        self.add_target(target);
+        self
+    }
+
+    ///
+    /// Registers a target like `to`, and returns a handle to remove it again.
+    ///
+    pub fn add_target<R: Target<T> + 'a>(&mut self, mut target: R) -> TargetHandle
+    {
+        let index = self.next_target;
+        let mut scopes = Vec::new();
+        let mut scope = self.parent;
+
+        self.next_target += 1;
+        target.describe_fields();
+
+        while let Some(parent) = scope
+        {
+            scopes.push(parent);
+            scope = parent.parent;
+        }
+
+        // values from nearer scopes take precedence, so they are injected last, and factories
+        // bound to a key that a nearer scope binds as well aren't called at all
+        for (depth, parent) in scopes.iter().enumerate().rev()
+        {
+            for provider in parent.providers.iter()
+            {
+                let shadowed = provider.key.as_deref().is_some_and(|key| {
+                    Self::binds(&self.providers, key) || scopes[..depth].iter().any(|scope| Self::binds(&scope.providers, key))
+                });
+
+                if shadowed
+                {
+                    continue;
+                }
+
+                if let Some(value) = Self::produced((provider.factory)(), depth + 1, provider.index, index, &mut self.failures)
+                {
+                    target.inject(value);
+                }
+            }
+        }
+
+        for provider in self.providers.iter_mut()
+        {
+            if let Some(value) = Self::produced((provider.factory)(), 0, provider.index, index, &mut self.failures)
+            {
+                provider.consumed |= target.inject(value);
+            }
+        }
+
+        self.targets.push((index, Box::new(target)));
+
+        TargetHandle(index)
+    }
+
+    ///
+    /// Removes a target, so it no longer receives values. Returns false if the target was removed
+    /// already. A borrowed target remains borrowed until the injector is dropped.
+    ///
+    pub fn remove_target(&mut self, handle: TargetHandle) -> bool
+    {
+        let Some(position) = self.targets.iter().position(|(index, _)| *index == handle.0) else {
+            return false;
+        };
+
+        self.targets.remove(position);
+        self.failures.retain(|failure| failure.target != handle.0);
+        true
+    }
+
+    ///
+    /// Clears the fields injected with the member identified by `key` in every registered target.
+    /// This pulls a dependency that is being torn down from all of its consumers. The factories for
+    /// the member stay registered; use `remove_factory` to keep them from being injected into new
+    /// targets. Returns true if any field was cleared.
+    ///
+    pub fn uninject(&mut self, key: T::Key) -> bool
+        where T: Keyed
+    {
+        let mut found = false;
+
+        for (_, target) in self.targets.iter_mut()
+        {
+            found |= target.uninject_matching(&mut |value| value.key() == key);
+        }
+
+        found
+    }
+
+    ///
+    /// Checks that every registered target has been fully injected, and that every factory has
+    /// been accepted by at least one target. All problems are collected into a single report.
+    ///
+    pub fn validate(&self) -> Result<(), ValidationError>
+    {
+        ValidationError::check(
+            self.targets.iter().map(|(index, target)| (*index, target.missing_injections())),
+            self.providers.iter().map(|provider| (provider.index, provider.consumed)),
+            &self.failures
+        )
+    }
+
+    fn binds(providers: &[Provider<'a, T>], key: &dyn FactoryKey) -> bool
+    {
+        providers.iter().any(|provider| provider.key.as_deref().is_some_and(|other| other.equals(key)))
+    }
+
+    fn produced(
+        result: Result<T, Arc<dyn Error + Send + Sync>>,
+        scope: usize,
+        factory_index: usize,
+        target_index: usize,
+        failures: &mut Vec<FailedInjection>
+    ) -> Option<T>
+    {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                failures.push(FailedInjection { scope, factory: factory_index, target: target_index, error });
+                None
+            }
+        }
+    }
+}
+
+///
+/// Resolves values from the injector's own factories first, followed by those of its parent scopes.
+/// Factories bound to another key are skipped, as are those of parent scopes when a nearer scope
+/// binds the key. The remaining factories are called, latest first, until one produces the
+/// requested member.
+///
+impl<'a, T: Clone + Keyed> Resolve<T> for Injector<'a, T>
+{
+    fn resolve<P, F: FnMut(T) -> Option<P>>(&self, key: T::Key, mut extract: F) -> Result<Option<P>, Arc<dyn Error + Send + Sync>>
+    {
+        let mut scope = Some(self);
+        let mut failure = None;
+
+        while let Some(injector) = scope
+        {
+            // the last factory wins, as it does when injecting into fields
+            let providers = injector.providers.iter().rev().filter(|provider| {
+                provider.key.as_deref().is_none_or(|bound| bound.equals(&key))
+            });
+
+            for provider in providers
+            {
+                match (provider.factory)()
+                {
+                    Ok(value) if value.key() == key => {
+                        if let Some(value) = extract(value)
+                        {
+                            return Ok(Some(value));
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        failure.get_or_insert(error);
+                    }
+                }
+            }
+
+            if Self::binds(&injector.providers, &key)
+            {
+                break;
+            }
+
+            scope = injector.parent;
+        }
+
+        failure.map_or(Ok(None), Err)
+    }
+}
+
+impl<'a, T: Clone> Default for Injector<'a, T>
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
@@ -106,46 +106,1345 @@
 //! let name = Rc::new(RefCell::new("Patje".to_string()));
 //! let age = 25;
 //!
-//! // we're going to inject injectee2, so wrap it in an Rc<Refcell<>>, or an Arc<Mutex/RwLock>
-//! let mut injectee1: Rc<RefCell<Injectee>> = Default::default();
+//! // we're going to inject injectee1, so wrap it in an Rc<Refcell<>>, or an Arc<Mutex/RwLock>
+//! let injectee1: Rc<RefCell<Injectee>> = Default::default();
 //! let mut injectee2: OtherInjectee = Default::default();
 //!
 //! // the order of inject and to calls does not matter
-//! let injector = Injector::new()
+//! let mut injector = Injector::new();
+//! injector
 //!     // inject sources. These need to be functions because usually, we're cloning a smart pointer or something
-//!     .inject(&|| Model::Name(Rc::clone(&name)))
-//!     .inject(&|| Model::Age(age))
-//!     .inject(&|| Model::Other(Rc::clone(&injectee1)))
-//!     // inject targets
-//!     // behind a RefCell, need to convert to ref
-//!     .to(&mut *injectee1.borrow_mut())
+//!     .inject(|| Model::Name(Rc::clone(&name)))
+//!     .inject(|| Model::Age(age))
+//!     .inject(|| Model::Other(Rc::clone(&injectee1)));
+//!
+//! // the injector can be passed around to register more targets
+//! fn register(injector: &mut Injector<'_, Model>, injectee: &Rc<RefCell<Injectee>>) {
+//!     // shared targets can be used while they're registered
+//!     injector.to(Rc::clone(injectee));
+//! }
+//! register(&mut injector, &injectee1);
+//!
+//! // borrowed targets remain borrowed until the injector is dropped
+//! injector.to(&mut injectee2);
+//! drop(injector);
+//!
+//! // The injected fields can be accessed like normal references
+//! assert_eq!(&*injectee1.borrow().name.borrow(), "Patje");
+//! assert_eq!(*injectee1.borrow().age, 25);
+//!
+//! // making sure the injected Injectee is the same
+//! let injected = injectee2.other.borrow();
+//! assert_eq!(&*injected.name.borrow(), "Patje");
+//! assert_eq!(*injected.age, 25);
+//! ```
+//!
+//! # Example: Owning targets with SharedInjector
+//!
+//! Borrowed targets can't be used while the injector is alive. A `SharedInjector` is an `Injector`
+//! that owns all of its factories and targets, which are shared handles wrapped in an
+//! `Rc<RefCell<_>>`, `Arc<Mutex<_>>` or `Arc<RwLock<_>>`. It can stay alive for the application's
+//! lifetime, and factories that are registered later still reach the existing targets.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injectable, SharedInjector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Name(Rc<RefCell<String>>),
+//!    Age(u32)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     #[inject(Model::Name)]
+//!     name: Injected<Rc<RefCell<String>>>,
+//!
+//!     #[inject(Model::Age)]
+//!     age: Injected<u32>
+//! }
+//!
+//! let name = Rc::new(RefCell::new("Patje".to_string()));
+//! let injectee1: Rc<RefCell<Injectee>> = Default::default();
+//! // targets can also be trait objects
+//! let injectee2: Rc<RefCell<dyn Injectable<Model>>> = Rc::new(RefCell::new(Injectee::default()));
+//!
+//! let mut injector = SharedInjector::new();
+//! injector
+//!     .inject(move || Model::Name(Rc::clone(&name)))
+//!     .to(Rc::clone(&injectee1))
+//!     .to(Rc::clone(&injectee2));
+//!
+//! // the targets can be used while they're registered
+//! assert_eq!(&*injectee1.borrow().name.borrow(), "Patje");
+//! assert!(!injectee2.borrow().is_fully_injected());
+//!
+//! // factories registered later still reach existing targets
+//! injector.inject(|| Model::Age(25));
+//! assert_eq!(*injectee1.borrow().age, 25);
+//! assert!(injector.validate().is_ok());
+//! ```
+//!
+//! # Example: Sharing an injector between threads
+//!
+//! `Injector` can't be sent to other threads. A `SyncInjector` only takes factories that are
+//! `Send + Sync`, and targets wrapped in an `Arc<Mutex<_>>` or `Arc<RwLock<_>>`. Its methods take
+//! `&self`, so it can be shared between threads and each thread can register its own factories and
+//! targets. Like `Injector`, it injects every factory into every target, and `bind` replaces the
+//! factory bound to the same key. Values are injected one at a time, and a value whose factory has
+//! been replaced in the meantime is dropped. Factories can call into the injector themselves.
+//! Registrations made from an `on_inject` hook are injected once the current injection has finished,
+//! and hooks must not call `validate`.
+//!
+//! ```
+//! use std::sync::{Arc, Mutex, RwLock};
+//! use std::thread;
+//! use injectiny::{Injected, Injectable, SyncInjector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Name(Arc<Mutex<String>>),
+//!    Age(u32)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     #[inject(Model::Name)]
+//!     name: Injected<Arc<Mutex<String>>>,
+//!
+//!     #[inject(Model::Age)]
+//!     age: Injected<u32>
+//! }
+//!
+//! let name = Arc::new(Mutex::new("Patje".to_string()));
+//! let injectee1: Arc<Mutex<Injectee>> = Default::default();
+//! let injectee2: Arc<RwLock<Injectee>> = Default::default();
+//!
+//! let injector = Arc::new(SyncInjector::new());
+//! injector.inject(move || Model::Name(Arc::clone(&name)));
+//!
+//! let worker = {
+//!     let injector = Arc::clone(&injector);
+//!     let injectee2 = Arc::clone(&injectee2);
+//!     thread::spawn(move || {
+//!         injector.to(injectee2).inject(|| Model::Age(25));
+//!     })
+//! };
+//! injector.to(Arc::clone(&injectee1));
+//! worker.join().unwrap();
+//!
+//! assert_eq!(*injectee1.lock().unwrap().name.lock().unwrap(), "Patje");
+//! assert_eq!(*injectee2.read().unwrap().age, 25);
+//! assert!(injector.validate().is_ok());
+//! ```
+//!
+//! # Example: Async factories
+//!
+//! Dependencies that are created by async constructors can be injected with an `AsyncInjector`. Its
+//! factories return futures, which are awaited by `inject_all`. The injector doesn't depend on any
+//! runtime, so it can be used with any executor that runs the future on the current thread.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::future::Future;
+//! use std::pin::pin;
+//! use std::rc::Rc;
+//! use std::task::{Context, Poll, Waker};
+//! use injectiny::{AsyncInjector, Injected};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Name(Rc<String>),
+//!    Age(u32)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     #[inject(Model::Name)]
+//!     name: Injected<Rc<String>>,
+//!
+//!     #[inject(Model::Age)]
+//!     age: Injected<u32>
+//! }
+//!
+//! // this could be loading from a database
+//! async fn load_name() -> Rc<String> {
+//!     Rc::new("Patje".to_string())
+//! }
+//!
+//! // a minimal executor; in real situations, this would be the application's runtime
+//! fn block_on<F: Future>(future: F) -> F::Output {
+//!     let mut future = pin!(future);
+//!     let mut context = Context::from_waker(Waker::noop());
+//!     loop {
+//!         if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+//!             return output;
+//!         }
+//!     }
+//! }
+//!
+//! let injectee: Rc<RefCell<Injectee>> = Default::default();
+//!
+//! let mut injector = AsyncInjector::new();
+//! injector
+//!     .inject(|| async { Model::Name(load_name().await) })
+//!     .inject(|| async { Model::Age(25) })
+//!     .to(Rc::clone(&injectee));
+//!
+//! // nothing is injected until the factories are awaited
+//! assert!(injector.validate().is_err());
+//!
+//! block_on(injector.inject_all());
+//!
+//! assert_eq!(injectee.borrow().name.as_str(), "Patje");
+//! assert_eq!(*injectee.borrow().age, 25);
+//! assert!(injector.validate().is_ok());
+//! ```
+//!
+//! # Example: Async factories on a multi-threaded executor
+//!
+//! The future returned by `AsyncInjector::inject_all` is not `Send`, so it can't be spawned on a
+//! multi-threaded executor. `SendAsyncInjector` requires its factories, their futures and its targets
+//! to be `Send`, which makes the future `Send` as well.
+//!
+//! ```
+//! use std::future::Future;
+//! use std::pin::pin;
+//! use std::sync::{Arc, Mutex};
+//! use std::task::{Context, Poll, Waker};
+//! use std::thread;
+//! use injectiny::{Injected, SendAsyncInjector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Name(Arc<String>)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     #[inject(Model::Name)]
+//!     name: Injected<Arc<String>>
+//! }
+//!
+//! async fn load_name() -> Arc<String> {
+//!     Arc::new("Patje".to_string())
+//! }
+//!
+//! fn block_on<F: Future>(future: F) -> F::Output {
+//!     let mut future = pin!(future);
+//!     let mut context = Context::from_waker(Waker::noop());
+//!     loop {
+//!         if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+//!             return output;
+//!         }
+//!     }
+//! }
+//!
+//! let injectee: Arc<Mutex<Injectee>> = Default::default();
+//!
+//! let mut injector = SendAsyncInjector::new();
+//! injector
+//!     .inject(|| async { Model::Name(load_name().await) })
+//!     .to(Arc::clone(&injectee));
+//!
+//! // a spawned task would be run by one of the executor's threads
+//! let task = async move {
+//!     injector.inject_all().await;
+//!     injector.validate().is_ok()
+//! };
+//!
+//! assert!(thread::spawn(move || block_on(task)).join().unwrap());
+//! assert_eq!(injectee.lock().unwrap().name.as_str(), "Patje");
+//! ```
+//!
+//! # Example: Replacing factories
+//!
+//! Factories registered with `bind` are bound to the key of the member they provide, which for an
+//! enum marked with `#[injectiny::model]` is its generated `{Enum}Key`. Binding another factory to the
+//! same key replaces the previous one, and injects its value into every registered target, so a
+//! service can be swapped out while the application is running. `rebind` does the same, and returns
+//! whether a factory was replaced. Factories registered with `inject` aren't bound to a key, and are
+//! never replaced.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injector, Lifetime};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! #[injectiny::model]
+//! enum Model {
+//!    Backend(Rc<String>),
+//!    Retries(u32)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Service
+//! {
+//!     #[inject(Model::Backend)]
+//!     backend: Injected<Rc<String>>,
+//!
+//!     #[inject(Model::Retries)]
+//!     retries: Injected<u32>
+//! }
+//!
+//! let service: Rc<RefCell<Service>> = Default::default();
+//!
+//! let mut injector = Injector::new();
+//! injector.bind(ModelKey::Backend, Lifetime::Singleton, || Model::Backend(Rc::new("sqlite".to_string())));
+//! injector.bind(ModelKey::Retries, Lifetime::Transient, || Model::Retries(3));
+//! injector.to(Rc::clone(&service));
+//!
+//! // the last factory for a key wins
+//! injector.bind(ModelKey::Retries, Lifetime::Transient, || Model::Retries(5));
+//! assert_eq!(*service.borrow().retries, 5);
+//!
+//! // hot-swap the backend
+//! let replaced = injector.rebind(ModelKey::Backend, Lifetime::Singleton, || Model::Backend(Rc::new("postgres".to_string())));
+//! assert!(replaced);
+//! assert_eq!(service.borrow().backend.as_str(), "postgres");
+//!
+//! // replaced factories are gone, so none of them is reported as unused
+//! assert!(injector.validate().is_ok());
+//! ```
+//!
+//! # Example: Removing registrations
+//!
+//! `add_target` and `add_factory` register a target or factory like `to` and `inject_with`, and
+//! return a handle that can be passed to `remove_target` or `remove_factory` later on. When the
+//! injector is shared through an `Rc<RefCell<_>>`, a handle can also be turned into a guard, which
+//! removes the registration when it is dropped. This lets a view detach itself when it is closed.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Lifetime, Registration, SharedInjector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Title(String)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct View
+//! {
+//!     #[inject(Model::Title)]
+//!     title: Injected<String>
+//! }
+//!
+//! struct Window {
+//!     view: Rc<RefCell<View>>,
+//!     _registration: Registration<'static, Model>
+//! }
+//!
+//! let injector = Rc::new(RefCell::new(SharedInjector::new()));
+//! let title = injector.borrow_mut().add_factory(Lifetime::Transient, || Model::Title("Untitled".to_string()));
+//!
+//! let window = {
+//!     let view: Rc<RefCell<View>> = Default::default();
+//!     let handle = injector.borrow_mut().add_target(Rc::clone(&view));
+//!     Window { view, _registration: handle.guard(&injector) }
+//! };
+//! assert_eq!(*window.view.borrow().title, "Untitled");
+//!
+//! // closing the window unregisters its view, so it no longer receives values
+//! let view = Rc::clone(&window.view);
+//! drop(window);
+//! injector.borrow_mut().inject(|| Model::Title("Renamed".to_string()));
+//! assert_eq!(*view.borrow().title, "Untitled");
+//!
+//! assert!(injector.borrow_mut().remove_factory(title));
+//! assert!(!injector.borrow_mut().remove_factory(title));
+//! ```
+//!
+//! # Example: Uninjecting dependencies
+//!
+//! An injected value can be taken out of its field again with `Injected::take`, or dropped with
+//! `Injected::reset`. `Injectable::uninject` clears all fields injected with a given enum member,
+//! identified by its key such as `ModelKey::Document`, and returns true if any field was cleared.
+//! `Injector::uninject` does this for every registered target, so a dependency that is being torn
+//! down can be pulled from all of its consumers before it is dropped.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injectable, Injected, Injector};
+//!
+//! #[derive(Clone)]
+//! #[injectiny::model]
+//! enum Model {
+//!    Document(Rc<RefCell<String>>),
+//!    Zoom(u32)
+//! }
+//!
+//! #[injectiny::injectable(Model)]
+//! #[derive(Default)]
+//! struct Editor
+//! {
+//!     #[inject]
+//!     document: Injected<Rc<RefCell<String>>>,
+//!
+//!     #[inject]
+//!     zoom: Injected<u32>
+//! }
+//!
+//! let document = Rc::new(RefCell::new("Hello".to_string()));
+//! let editor: Rc<RefCell<Editor>> = Default::default();
+//!
+//! let mut injector = Injector::new();
+//! injector
+//!     .inject(|| Model::Document(Rc::clone(&document)))
+//!     .inject(|| Model::Zoom(100))
+//!     .to(Rc::clone(&editor));
+//!
+//! // the document is closed, so every consumer lets go of it
+//! assert!(injector.uninject(ModelKey::Document));
+//! assert!(!editor.borrow().document.is_injected());
+//! assert_eq!(Rc::strong_count(&document), 1);
+//!
+//! let zoom = editor.borrow_mut().zoom.take();
+//! assert_eq!(zoom, Some(100));
+//! assert!(!editor.borrow().is_fully_injected());
+//! ```
+//!
+//! # Example: Reacting to injections
+//!
+//! An injector can replace a field's value at any time, for instance when a factory is rebound.
+//! Adding `on_inject = "method"` to `#[inject]` makes the generated code call that method after the
+//! field has been assigned, with the previous value, if any, and the new one. The method takes
+//! `&Option<T>` and `&T`, where `T` is the field's payload type.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Document(Rc<String>)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct View
+//! {
+//!     #[inject(Model::Document, on_inject = "document_changed")]
+//!     document: Injected<Rc<String>>,
+//!
+//!     refreshes: Vec<String>
+//! }
+//!
+//! impl View {
+//!     fn document_changed(&mut self, old: &Option<Rc<String>>, new: &Rc<String>) {
+//!         let old = old.as_ref().map_or("nothing", |old| old.as_str());
+//!         self.refreshes.push(format!("{} -> {}", old, new));
+//!     }
+//! }
+//!
+//! let view: Rc<RefCell<View>> = Default::default();
+//!
+//! let mut injector = Injector::new();
+//! injector
+//!     .inject(|| Model::Document(Rc::new("draft".to_string())))
+//!     .to(Rc::clone(&view));
+//! injector.inject(|| Model::Document(Rc::new("final".to_string())));
+//!
+//! assert_eq!(view.borrow().refreshes, ["nothing -> draft", "draft -> final"]);
+//! ```
+//!
+//! # Example: Observing models
+//!
+//! `Observable` is a shared, mutable model that can be used as an enum payload instead of an
+//! `Rc<RefCell<_>>`. Every struct it is injected into shares the same value, and can subscribe to
+//! be notified of changes. A subscription lasts until the returned `Subscription` is dropped.
+//! Combined with `on_inject`, a view can subscribe again whenever its model is replaced.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injector, Observable, Subscription};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Name(Observable<String>)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Label
+//! {
+//!     #[inject(Model::Name, on_inject = "name_changed")]
+//!     name: Injected<Observable<String>>,
+//!
+//!     text: Rc<RefCell<String>>,
+//!     subscription: Option<Subscription>
+//! }
+//!
+//! impl Label {
+//!     fn name_changed(&mut self, _old: &Option<Observable<String>>, new: &Observable<String>) {
+//!         *self.text.borrow_mut() = new.get().clone();
+//!
+//!         let text = Rc::clone(&self.text);
+//!         self.subscription = Some(new.subscribe(move |name| *text.borrow_mut() = name.clone()));
+//!     }
+//! }
+//!
+//! let name = Observable::new("Patje".to_string());
+//! let other = Observable::new("Other".to_string());
+//! let label: Rc<RefCell<Label>> = Default::default();
+//!
+//! let mut injector = Injector::new();
+//! injector
+//!     .inject(|| Model::Name(name.clone()))
+//!     .to(Rc::clone(&label));
+//! assert_eq!(*label.borrow().text.borrow(), "Patje");
+//!
+//! // changes to the model reach the view
+//! name.set("Patje Jr.".to_string());
+//! assert_eq!(*label.borrow().text.borrow(), "Patje Jr.");
+//!
+//! // a new model replaces the subscription to the old one
+//! injector.inject(|| Model::Name(other.clone()));
+//! name.set("Ignored".to_string());
+//! other.update(|name| name.push('!'));
+//! assert_eq!(*label.borrow().text.borrow(), "Other!");
+//!
+//! // subscribing through Injected reports a missing injection instead of panicking
+//! let empty: Injected<Observable<String>> = Default::default();
+//! assert!(empty.try_subscribe(|_| {}).is_err());
+//! ```
+//!
+//! # Example: Collecting every value of a member
+//!
+//! A field normally holds the last value injected into it. Marking it with `collect` makes it gather
+//! every injected value instead, which is useful for plugins or handlers. Such a field must be of
+//! type `Injected<Vec<T>>`. Every factory registered with `inject` is injected into every target, so a
+//! collected member can be provided by several factories. Replacing a factory with `rebind` clears the
+//! member before the new value is collected, and so does `AsyncInjector::inject_all` before it injects
+//! the values again.
+//!
+//! ```
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Handler(Rc<dyn Fn(&str) -> String>)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Dispatcher
+//! {
+//!     #[inject(Model::Handler, collect)]
+//!     handlers: Injected<Vec<Rc<dyn Fn(&str) -> String>>>
+//! }
+//!
+//! let mut dispatcher: Dispatcher = Default::default();
+//!
+//! let mut injector = Injector::new();
+//! injector
+//!     .inject(|| Model::Handler(Rc::new(|event| format!("logged {}", event))))
+//!     .inject(|| Model::Handler(Rc::new(|event| format!("sent {}", event))))
+//!     .to(&mut dispatcher);
+//! drop(injector);
+//!
+//! let results: Vec<_> = dispatcher.handlers.iter().map(|handler| handler("click")).collect();
+//! assert_eq!(results, ["logged click", "sent click"]);
+//! ```
+//!
+//! # Example: Scoped child injectors
+//!
+//! Parts of an application, such as windows or requests, can get their own scope with
+//! `Injector::child`. A child sees all factories of its parent, but the values of its own factories
+//! take precedence. A factory that the child binds to a key shadows the parent's factories for that
+//! key, so those aren't called for the child's targets at all. Dropping the child releases its
+//! targets and factories, while the parent stays unchanged.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injector, Lifetime};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! #[injectiny::model]
+//! enum Model {
+//!    Name(String),
+//!    Theme(String)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Window
+//! {
+//!     #[inject(Model::Name)]
+//!     name: Injected<String>,
+//!
+//!     #[inject(Model::Theme)]
+//!     theme: Injected<String>
+//! }
+//!
+//! let main_window: Rc<RefCell<Window>> = Default::default();
+//! let settings_window: Rc<RefCell<Window>> = Default::default();
+//!
+//! let mut root = Injector::new();
+//! root.inject(|| Model::Name("Patje".to_string()));
+//! root.bind(ModelKey::Theme, Lifetime::Transient, || Model::Theme("light".to_string()));
+//! root.to(Rc::clone(&main_window));
+//!
+//! {
+//!     // the settings window overrides the theme, but shares the name
+//!     let mut scope = root.child();
+//!     scope.bind(ModelKey::Theme, Lifetime::Transient, || Model::Theme("dark".to_string()));
+//!     scope.to(Rc::clone(&settings_window));
+//!
+//!     assert!(scope.validate().is_ok());
+//! }
+//!
+//! assert_eq!(*settings_window.borrow().name, "Patje");
+//! assert_eq!(*settings_window.borrow().theme, "dark");
+//! assert_eq!(*main_window.borrow().theme, "light");
+//! ```
+//!
+//! # Example: Inferring enum members
+//!
+//! Naming the enum member on every field can become repetitive for larger models. When the enum is
+//! marked with `#[injectiny::model]`, a bare `#[inject]` finds the member from the field type. This
+//! only works for payload types that are carried by a single member; for any other type, the member
+//! must still be named explicitly.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injectable};
+//!
+//! #[derive(Clone)]
+//! #[injectiny::model]
+//! enum Model {
+//!    Name(Rc<RefCell<String>>),
+//!    Width(u32),
+//!    Height(u32)
+//! }
+//!
+//! #[injectiny::injectable(Model)]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     // Model::Name is the only member carrying a Rc<RefCell<String>>
+//!     #[inject]
+//!     name: Injected<Rc<RefCell<String>>>,
+//!
+//!     // Model::Width and Model::Height are both u32, so the member needs to be named
+//!     #[inject(Model::Width)]
+//!     width: Injected<u32>
+//! }
+//!
+//! let mut injectee: Injectee = Default::default();
+//! injectee.inject(Model::Name(Rc::new(RefCell::new("Patje".to_string()))));
+//! injectee.inject(Model::Width(640));
+//!
+//! assert_eq!(&*injectee.name.borrow(), "Patje");
+//! assert_eq!(*injectee.width, 640);
+//! ```
+//!
+//! # Example: Generic structs and models
+//!
+//! `#[injectable]` carries the struct's generic parameters, lifetimes and where clauses over to the
+//! generated implementation, and the model enum can be generic as well. The implementation only
+//! exists for arguments that make the model `Clone`.
+//!
+//! ```
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injectable};
+//! use injectiny_proc_macro::injectable;
+//!
+//! trait Backend {
+//!     fn draw(&self, text: &str) -> String;
+//! }
+//!
+//! // derived Clone and Default impls require the same of B
+//! #[derive(Clone, Default)]
+//! struct Terminal;
+//!
+//! impl Backend for Terminal {
+//!     fn draw(&self, text: &str) -> String {
+//!         format!("> {}", text)
+//!     }
+//! }
+//!
+//! #[derive(Clone)]
+//! enum Model<B> {
+//!    Backend(Rc<B>),
+//!    Title(String)
+//! }
+//!
+//! #[injectable(Model<B>)]
+//! #[derive(Default)]
+//! struct View<'a, B: Backend>
+//!     where B: 'a
+//! {
+//!     #[inject(Model::Backend)]
+//!     backend: Injected<Rc<B>>,
+//!
+//!     #[inject(Model::Title)]
+//!     title: Injected<String>,
+//!
+//!     prefix: Option<&'a str>
+//! }
+//!
+//! let mut view: View<Terminal> = Default::default();
+//! view.inject(Model::Backend(Rc::new(Terminal)));
+//! view.inject(Model::Title("Patje".to_string()));
+//!
+//! assert_eq!(view.backend.draw(&view.title), "> Patje");
+//! assert!(view.prefix.is_none());
+//! ```
+//!
+//! # Example: Multiple model enums
+//!
+//! A struct can take its dependencies from more than one model enum. `#[injectable(Core, Ui)]`
+//! implements `Injectable` once for each enum, and every field is injected through the
+//! implementation of its member's enum. The member must therefore be named on each field.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injectable, Injector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Core {
+//!    Name(String)
+//! }
+//!
+//! #[derive(Clone)]
+//! enum Ui {
+//!    Title(String)
+//! }
+//!
+//! #[injectable(Core, Ui)]
+//! #[derive(Default)]
+//! struct Window {
+//!     #[inject(Core::Name)]
+//!     name: Injected<String>,
+//!
+//!     #[inject(Ui::Title)]
+//!     title: Injected<String>
+//! }
+//!
+//! let window = Rc::new(RefCell::new(Window::default()));
+//!
+//! let mut core = Injector::new();
+//! core.inject(|| Core::Name("Patje".to_string()));
+//! core.to(window.clone());
+//!
+//! // each implementation only reports the fields of its own enum
+//! assert!(Injectable::<Core>::is_fully_injected(&*window.borrow()));
+//! assert!(!Injectable::<Ui>::is_fully_injected(&*window.borrow()));
+//!
+//! let mut ui = Injector::new();
+//! ui.inject(|| Ui::Title("Hello".to_string()));
+//! ui.to(window.clone());
+//!
+//! assert_eq!(format!("{}, {}", *window.borrow().title, *window.borrow().name), "Hello, Patje");
+//! ```
+//!
+//! # Example: Tuple structs
+//!
+//! Fields of tuple structs can be injected as well, which makes `#[injectable]` and
+//! `#[derive(Construct)]` work on newtypes. Missing fields are reported by their position.
+//!
+//! ```
+//! use injectiny::{Construct, Injected, Injectable, Injector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! #[injectiny::model]
+//! enum Model {
+//!    Name(String),
+//!    Age(u32)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Name(#[inject(Model::Name)] Injected<String>);
+//!
+//! let mut name: Name = Default::default();
+//! name.inject(Model::Name("Patje".to_string()));
+//! assert_eq!(*name.0, "Patje");
+//!
+//! #[derive(Construct, Debug)]
+//! #[construct(Model)]
+//! struct Person(#[inject(Model::Name)] String, #[inject(Model::Age)] u32);
+//!
+//! let mut injector = Injector::new();
+//! injector.inject(|| Model::Name("Patje".to_string()));
+//!
+//! let missing = Person::construct(&injector).unwrap_err();
+//! assert_eq!(missing.points()[0].field, "1");
+//!
+//! injector.inject(|| Model::Age(42));
+//! let person = Person::construct(&injector).unwrap();
+//! assert_eq!((person.0.as_str(), person.1), ("Patje", 42));
+//! ```
+//!
+//! # Example: Model helpers
+//!
+//! `#[injectiny::model]` also generates a few helpers for the model enum, which must implement
+//! `Clone`:
+//! - a fieldless `{Enum}Key` enum, with a `key()` method to get the key of a value
+//! - `From` implementations to build a value from its payload
+//! - `TryFrom` implementations to get a payload back out of a value
+//!
+//! As with a bare `#[inject]`, the conversions are only generated for payload types that are carried
+//! by a single member. Payload types are compared as written, by the last segment of their paths, so
+//! `String` and `std::string::String` are the same type, but a type alias is not recognized. Two
+//! members whose payloads are the same type under different names, such as `Age(u32)` and
+//! `Visits(Count)` with `type Count = u32`, produce conflicting implementations; the compiler reports
+//! these at the second member. Use a newtype for one of the payloads instead.
+//!
+//! ```
+//! use injectiny::Keyed;
+//!
+//! #[derive(Clone)]
+//! #[injectiny::model]
+//! enum Model {
+//!    Name(String),
+//!    Width(u32),
+//!    Height(u32)
+//! }
+//!
+//! let model = Model::from("Patje".to_string());
+//! assert_eq!(model.key(), ModelKey::Name);
+//! assert_eq!(Model::Height(480).key(), ModelKey::Height);
+//!
+//! // Keyed allows working with the keys of any model
+//! fn keys<T: Keyed>(models: &[T]) -> Vec<T::Key> {
+//!     models.iter().map(Keyed::key).collect()
+//! }
+//! assert_eq!(keys(&[model.clone(), Model::Width(640)]), vec![ModelKey::Name, ModelKey::Width]);
+//!
+//! assert_eq!(String::try_from(model).ok(), Some("Patje".to_string()));
+//! assert!(String::try_from(Model::Width(640)).is_err());
+//! ```
+//!
+//! # Example: Constructor injection
+//!
+//! Instead of filling `Injected` fields of an existing struct, `#[derive(Construct)]` builds the
+//! struct from plain fields. It generates a `construct` function that takes anything implementing
+//! `Resolve`, such as an `Injector`, and resolves every `#[inject]` field from its factories. Other
+//! fields are set to their default value. If any field can't be resolved, all of them are reported
+//! at once, along with the errors of factories that failed to provide them. The model must be
+//! `Keyed`, as generated by `#[injectiny::model]`: factories bound to another member are not called.
+//!
+//! ```
+//! use std::rc::Rc;
+//! use injectiny::{Construct, Injector};
+//!
+//! #[derive(Clone)]
+//! #[injectiny::model]
+//! enum Model {
+//!    Name(Rc<String>),
+//!    Width(u32),
+//!    Height(u32)
+//! }
+//!
+//! #[derive(Construct)]
+//! #[construct(Model)]
+//! struct Window
+//! {
+//!     // the member is inferred, as with #[injectable]
+//!     #[inject]
+//!     title: Rc<String>,
+//!
+//!     #[inject(Model::Width)]
+//!     width: u32,
+//!
+//!     #[inject(Model::Height)]
+//!     height: u32,
+//!
+//!     visible: bool
+//! }
+//!
+//! let mut injector = Injector::new();
+//! injector
+//!     .inject(|| Model::Name(Rc::new("Patje".to_string())))
+//!     .inject(|| Model::Width(640));
+//!
+//! // the height isn't provided yet
+//! let missing = Window::construct(&injector).err().unwrap();
+//! assert_eq!(missing.points()[0].field, "height");
+//!
+//! injector.inject(|| Model::Height(480));
+//! let window = Window::construct(&injector).unwrap();
+//!
+//! assert_eq!(*window.title, "Patje");
+//! assert_eq!((window.width, window.height), (640, 480));
+//! assert!(!window.visible);
+//! ```
+//!
+//! # Example: Injection by type
+//!
+//! Instead of a central model enum, dependencies can also be looked up by type. Leaving out the enum
+//! in `#[injectable]` makes every `#[inject]` field receive a value of its own type. These values are
+//! provided by a `Registry`, which maps each type to a factory. Separate registries, for instance one
+//! per crate, can be merged into one.
+//!
+//! ```
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injectable, Registry};
+//!
+//! #[derive(Clone, Default)]
+//! struct Settings {
+//!     volume: u32
+//! }
+//!
+//! #[injectiny::injectable]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     #[inject]
+//!     settings: Injected<Rc<RefCell<Settings>>>,
+//!
+//!     #[inject]
+//!     name: Injected<String>
+//! }
+//!
+//! let settings = Rc::new(RefCell::new(Settings { volume: 11 }));
+//!
+//! // this could live in one crate
+//! let mut core = Registry::new();
+//! core.bind(move || Rc::clone(&settings));
+//!
+//! // and this in another
+//! let mut ui = Registry::new();
+//! ui.bind(|| "Patje".to_string());
+//!
+//! let mut registry = Registry::new();
+//! registry.extend(core).extend(ui);
+//!
+//! let mut injectee: Injectee = Default::default();
+//! registry.inject_into(&mut injectee);
+//!
+//! assert!(injectee.is_fully_injected());
+//! assert_eq!(injectee.settings.borrow().volume, 11);
+//! assert_eq!(*injectee.name, "Patje");
+//! ```
+//!
+//! # Example: Validating an Injector
+//!
+//! Once everything has been registered, `Injector::validate` checks that every target has been
+//! fully injected, and that every factory was used by at least one target. All problems are
+//! collected into a single report, so incomplete wiring can be reported at startup.
+//!
+//! ```
+//! use injectiny::{Injected, Injector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Name(String),
+//!    Age(u32),
+//!    Score(f32)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     #[inject(Model::Name)]
+//!     name: Injected<String>,
+//!
+//!     #[inject(Model::Age)]
+//!     age: Injected<u32>
+//! }
+//!
+//! let mut injectee: Injectee = Default::default();
+//!
+//! let result = Injector::new()
+//!     .inject(&|| Model::Name("Patje".to_string()))
+//!     .inject(&|| Model::Score(0.5))
+//!     .to(&mut injectee)
+//!     .validate();
+//!
+//! let error = result.unwrap_err();
+//!
+//! // the age was never injected
+//! let incomplete = &error.incomplete_targets()[0];
+//! assert_eq!(incomplete.missing()[0].variant, "Model::Age");
+//!
+//! // no target accepts the score
+//! assert_eq!(error.unused_factories(), &[1]);
+//! ```
+//!
+//! # Example: Singleton factories
+//!
+//! By default, a factory is called once for every target. Expensive factories, or factories with side
+//! effects, can be registered with `inject_with` and `Lifetime::Singleton` instead: the factory is then
+//! called only once, and every target receives a clone of its value.
+//!
+//! ```
+//! use std::cell::Cell;
+//! use std::rc::Rc;
+//! use injectiny::{Injected, Injector, Lifetime};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Config(Rc<String>),
+//!    Id(u32)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     #[inject(Model::Config)]
+//!     config: Injected<Rc<String>>,
+//!
+//!     #[inject(Model::Id)]
+//!     id: Injected<u32>
+//! }
+//!
+//! let parsed = Cell::new(0);
+//! let next_id = Cell::new(0);
+//!
+//! let mut injectee1: Injectee = Default::default();
+//! let mut injectee2: Injectee = Default::default();
+//!
+//! let mut injector = Injector::new();
+//! injector
+//!     .inject_with(Lifetime::Singleton, || {
+//!         parsed.set(parsed.get() + 1);
+//!         Model::Config(Rc::new("volume = 11".to_string()))
+//!     })
+//!     .inject_with(Lifetime::Transient, || {
+//!         next_id.set(next_id.get() + 1);
+//!         Model::Id(next_id.get())
+//!     })
+//!     .to(&mut injectee1)
 //!     .to(&mut injectee2);
+//! drop(injector);
 //!
-//! // The injected fields can be accessed like normal references
-//! assert_eq!(&*injectee1.borrow().name.borrow(), "Patje");
-//! assert_eq!(*injectee1.borrow().age, 25);
+//! // the config was parsed once, and both targets share it
+//! assert_eq!(parsed.get(), 1);
+//! assert!(Rc::ptr_eq(&injectee1.config, &injectee2.config));
 //!
-//! // making sure the injected Injectee is the same
-//! let injected = injectee2.other.borrow();
-//! assert_eq!(&*injected.name.borrow(), "Patje");
-//! assert_eq!(*injected.age, 25);
+//! // every target received its own id
+//! assert_eq!((*injectee1.id, *injectee2.id), (1, 2));
+//! ```
+//!
+//! # Example: Fallible factories
+//!
+//! Factories that can fail are registered with `try_inject`. A failing factory doesn't panic or stop
+//! the wiring: every failure is collected along with the target the value was meant for, and
+//! reported by `validate`, so all misconfigured services can be reported at once.
+//!
+//! ```
+//! use injectiny::{Injected, Injector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Name(String),
+//!    Port(u16)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     #[inject(Model::Name)]
+//!     name: Injected<String>,
+//!
+//!     #[inject(Model::Port)]
+//!     port: Injected<u16>
+//! }
+//!
+//! let mut injectee1: Injectee = Default::default();
+//! let mut injectee2: Injectee = Default::default();
+//!
+//! let mut injector = Injector::new();
+//! injector
+//!     .inject(|| Model::Name("Patje".to_string()))
+//!     .try_inject(|| "http".parse::<u16>().map(Model::Port))
+//!     .to(&mut injectee1)
+//!     .to(&mut injectee2);
+//!
+//! let error = injector.validate().unwrap_err();
+//!
+//! // the port factory failed for both targets
+//! let failed = error.failed_injections();
+//! assert_eq!(failed.len(), 2);
+//! assert_eq!((failed[0].factory(), failed[0].target()), (1, 0));
+//! assert_eq!((failed[1].factory(), failed[1].target()), (1, 1));
+//! assert_eq!(failed[0].error().to_string(), "invalid digit found in string");
+//! ```
+//!
+//! # Example: Handling missing injections
+//!
+//! Dereferencing an `Injected` field that was never injected panics. Once the struct has been
+//! registered with an injector, the message names the field, the struct and the enum member. A struct
+//! that was only created with `Default` can't know this yet, and reports a generic message instead. Use `try_get` or `try_get_mut` to handle missing values, or check the
+//! whole struct up front with `is_fully_injected` and `missing_injections`.
+//!
+//! ```
+//! use injectiny::{Injected, Injectable, Injector};
+//! use injectiny_proc_macro::injectable;
+//!
+//! #[derive(Clone)]
+//! enum Model {
+//!    Name(String),
+//!    Age(u32)
+//! }
+//!
+//! #[injectable(Model)]
+//! #[derive(Default)]
+//! struct Injectee
+//! {
+//!     #[inject(Model::Name)]
+//!     name: Injected<String>,
+//!
+//!     #[inject(Model::Age)]
+//!     age: Injected<u32>
+//! }
+//!
+//! let mut injectee: Injectee = Default::default();
+//!
+//! // nothing has told the struct where its fields are injected yet
+//! let error = injectee.age.try_get().unwrap_err();
+//! assert!(error.point().is_none());
+//! assert_eq!(error.to_string(), "Injected value was accessed before it was injected");
+//!
+//! let mut injector = Injector::new();
+//! injector.to(&mut injectee);
+//! drop(injector);
+//!
+//! let error = injectee.name.try_get().unwrap_err();
+//! assert_eq!(
+//!     error.to_string(),
+//!     "field `name` of `Injectee` was accessed before `Model::Name` was injected"
+//! );
+//!
+//! injectee.inject(Model::Age(25));
+//!
+//! assert_eq!(injectee.age.try_get(), Ok(&25));
+//! assert!(!injectee.is_fully_injected());
+//!
+//! let missing = injectee.missing_injections();
+//! assert_eq!(missing.len(), 1);
+//! assert_eq!(missing[0].field, "name");
+//! assert_eq!(missing[0].variant, "Model::Name");
 //! ```
 
 
 extern crate injectiny_proc_macro;
 
+pub use injectiny_proc_macro::{injectable, model, Construct};
+pub use async_injector::{AsyncInjector, SendAsyncInjector};
+pub use construct::{Missing, Resolve};
+pub use injector::{FactoryHandle, Injector, Lifetime, Registration, SharedInjector, TargetHandle};
+pub use observable::{Observable, Subscription};
+pub use registry::{Binding, Registry};
+pub use sync::SyncInjector;
+pub use target::Target;
+
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::Hash;
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+mod async_injector;
+mod construct;
+mod injector;
+mod observable;
+mod registry;
+mod sync;
+mod target;
 
 pub trait Injectable<T: Clone> {
     fn inject(&mut self, value: T);
+
+    ///
+    /// Returns the injection points of all fields that have not been injected yet.
+    ///
+    fn missing_injections(&self) -> Vec<InjectionPoint> {
+        Vec::new()
+    }
+
+    ///
+    /// Returns true if all injectable fields have been injected.
+    ///
+    fn is_fully_injected(&self) -> bool {
+        self.missing_injections().is_empty()
+    }
+
+    ///
+    /// Records where each field is injected, so accessing a field that is still missing reports
+    /// its name and enum member. Injectors call this when the struct is registered as a target.
+    ///
+    fn describe_fields(&mut self) {}
+
+    ///
+    /// Returns true if injecting the given value would fill one of this struct's fields.
+    ///
+    fn accepts(&self, value: &T) -> bool {
+        let _ = value;
+        true
+    }
+
+    ///
+    /// Clears every injected field whose value, wrapped in its enum member, is matched by
+    /// `matches`. Returns true if any field was cleared. This is implemented by `#[injectable]`,
+    /// and used by `uninject`.
+    ///
+    fn uninject_matching(&mut self, matches: &mut dyn FnMut(&T) -> bool) -> bool {
+        let _ = matches;
+        false
+    }
+
+    ///
+    /// Clears every field that was injected with the member identified by `key`, such as
+    /// `ModelKey::Name`. Returns true if any field was cleared.
+    ///
+    fn uninject(&mut self, key: T::Key) -> bool
+        where T: Keyed
+    {
+        self.uninject_matching(&mut |value| value.key() == key)
+    }
+}
+
+///
+/// Maps a payload type to the enum member carrying it. `#[model]` implements this for every payload
+/// type that is used by exactly one member, which allows a bare `#[inject]` to find the member from
+/// the field type.
+///
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no unique member with a `{P}` payload",
+    label = "cannot infer the enum member for this field",
+    note = "a bare #[inject] requires the enum to be marked with #[injectiny::model] and to have exactly one member of type `{P}`; otherwise, name the member with #[inject(Enum::Member)]"
+)]
+pub trait HasVariant<P>: Keyed + Sized {
+    /// The name of the member carrying the payload, for instance `Model::Name`.
+    const VARIANT: &'static str;
+
+    /// The key of the member carrying the payload, for instance `ModelKey::Name`.
+    const KEY: Self::Key;
+
+    ///
+    /// Wraps the payload in its enum member.
+    ///
+    fn from_payload(payload: P) -> Self;
+
+    ///
+    /// Returns the payload if this is the member carrying it, or gives back the value otherwise.
+    ///
+    fn into_payload(self) -> Result<P, Self>;
+
+    ///
+    /// Returns true if this is the member carrying the payload.
+    ///
+    fn has_payload(&self) -> bool;
+}
+
+///
+/// Identifies the members of a model enum without their payloads. `#[model]` implements this with a
+/// fieldless `{Enum}Key` enum, and `Binding` with the TypeId of its value. Keys are used to bind
+/// factories to a member with `Injector::bind`.
+///
+pub trait Keyed {
+    type Key: Copy + Eq + Hash + Debug + 'static;
+
+    ///
+    /// Returns the key of this value's member.
+    ///
+    fn key(&self) -> Self::Key;
 }
 
+///
+/// Describes where a value is injected: the struct, the field and the enum member that fills it.
+/// These are recorded by the `#[injectable]` macro and used to report missing injections.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InjectionPoint {
+    /// The name of the struct containing the field.
+    pub target: &'static str,
+    /// The name of the injected field.
+    pub field: &'static str,
+    /// The enum member the field is injected with, as written in `#[inject(...)]`. For structs
+    /// without a model enum, this is the name of the injected type.
+    pub variant: &'static str
+}
+
+impl Display for InjectionPoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "field `{}` of `{}` (injected with `{}`)", self.field, self.target, self.variant)
+    }
+}
+
+///
+/// The error returned when accessing an Injected value that has not been injected yet.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotInjected {
+    point: Option<InjectionPoint>
+}
+
+impl NotInjected {
+    ///
+    /// Returns where the missing value should have been injected, if known. This is only known
+    /// for fields of `#[injectable]` structs that have been registered with an injector.
+    ///
+    pub fn point(&self) -> Option<&InjectionPoint> {
+        self.point.as_ref()
+    }
+}
+
+impl Display for NotInjected {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.point {
+            Some(point) => write!(
+                f, "field `{}` of `{}` was accessed before `{}` was injected",
+                point.field, point.target, point.variant
+            ),
+            None => write!(f, "Injected value was accessed before it was injected")
+        }
+    }
+}
+
+impl Error for NotInjected {}
+
 ///
 /// Injected is a wrapper around a value that can be injected into a struct. All injected members
 /// must be wrapped by this type.
 ///
 pub struct Injected<T> {
-    value: Option<T>
+    value: Option<T>,
+    point: Option<InjectionPoint>
 }
 
 impl<T> Injected<T> {
@@ -154,7 +1453,8 @@ impl<T> Injected<T> {
     ///
     pub fn from(value: T) -> Self {
         Self {
-            value: Some(value)
+            value: Some(value),
+            point: None
         }
     }
 
@@ -164,12 +1464,77 @@ impl<T> Injected<T> {
     pub fn is_injected(&self) -> bool {
         self.value.is_some()
     }
+
+    ///
+    /// Returns a reference to the injected value, or a NotInjected error if nothing was injected.
+    ///
+    pub fn try_get(&self) -> Result<&T, NotInjected> {
+        match &self.value {
+            Some(value) => Ok(value),
+            None => Err(self.not_injected())
+        }
+    }
+
+    ///
+    /// Returns a mutable reference to the injected value, or a NotInjected error if nothing was
+    /// injected.
+    ///
+    pub fn try_get_mut(&mut self) -> Result<&mut T, NotInjected> {
+        let error = self.not_injected();
+        self.value.as_mut().ok_or(error)
+    }
+
+    ///
+    /// Returns a mutable reference to the injected value, injecting the result of `default` first
+    /// if nothing was injected yet.
+    ///
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, default: F) -> &mut T {
+        self.value.get_or_insert_with(default)
+    }
+
+    ///
+    /// Injects the value, replacing the previous one. Unlike assigning `Injected::from`, this keeps
+    /// the injection point recorded for the field.
+    ///
+    pub fn set(&mut self, value: T) {
+        self.value = Some(value);
+    }
+
+    ///
+    /// Takes the injected value out, leaving the field empty as if it was never injected.
+    ///
+    pub fn take(&mut self) -> Option<T> {
+        self.value.take()
+    }
+
+    ///
+    /// Drops the injected value, leaving the field empty as if it was never injected.
+    ///
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+
+    ///
+    /// Records where this value is injected. This is called by the code generated by
+    /// `#[injectable]` so errors can name the field and enum member.
+    ///
+    #[doc(hidden)]
+    pub fn describe(&mut self, point: InjectionPoint) {
+        self.point = Some(point);
+    }
+
+    fn not_injected(&self) -> NotInjected {
+        NotInjected {
+            point: self.point
+        }
+    }
 }
 
 impl<T> Default for Injected<T> {
     fn default() -> Self {
         Self {
-            value: None
+            value: None,
+            point: None
         }
     }
 }
@@ -178,57 +1543,223 @@ impl<T> Deref for Injected<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        self.value.as_ref().unwrap()
+        self.try_get().unwrap_or_else(|error| panic!("{}", error))
     }
 }
 
 impl<T> DerefMut for Injected<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.value.as_mut().unwrap()
+        self.try_get_mut().unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+
+///
+/// A target registered with an Injector that still has fields waiting to be injected.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncompleteTarget {
+    target: usize,
+    missing: Vec<InjectionPoint>
+}
+
+impl IncompleteTarget {
+    ///
+    /// Returns the index of the target, in the order it was registered with `to`.
+    ///
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    ///
+    /// Returns the fields of the target that have not been injected.
+    ///
+    pub fn missing(&self) -> &[InjectionPoint] {
+        &self.missing
+    }
+}
+
+///
+/// A fallible factory that returned an error when its value was needed for a target.
+///
+#[derive(Clone, Debug)]
+pub struct FailedInjection {
+    scope: usize,
+    factory: usize,
+    target: usize,
+    error: Arc<dyn Error + Send + Sync>
+}
+
+impl FailedInjection {
+    ///
+    /// Returns the scope that registered the factory, counted from the injector that reports the
+    /// failure: 0 for its own factories, 1 for those of its parent, and so on.
+    ///
+    pub fn scope(&self) -> usize {
+        self.scope
+    }
+
+    ///
+    /// Returns the index of the factory in its scope, in the order it was registered with `inject`
+    /// or `try_inject`.
+    ///
+    pub fn factory(&self) -> usize {
+        self.factory
+    }
+
+    ///
+    /// Returns the index of the target the value was meant for, in the order it was registered
+    /// with `to`.
+    ///
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    ///
+    /// Returns the error returned by the factory.
+    ///
+    pub fn error(&self) -> &(dyn Error + Send + Sync + 'static) {
+        &*self.error
     }
 }
 
+impl PartialEq for FailedInjection {
+    fn eq(&self, other: &Self) -> bool {
+        // errors can't be compared, so they're considered equal if they report the same message
+        self.scope == other.scope && self.factory == other.factory && self.target == other.target
+            && self.error.to_string() == other.error.to_string()
+    }
+}
+
+impl Eq for FailedInjection {}
 
 ///
-/// Injector is a convenience struct that can making injecting things a bit more ergonomic.
+/// The report returned by `Injector::validate` when the wiring is incomplete, or when fallible
+/// factories failed.
 ///
-pub struct Injector<'a, T: Clone>
-{
-    factories: Vec<&'a dyn Fn() -> T>,
-    targets: Vec<&'a mut dyn Injectable<T>>
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    incomplete_targets: Vec<IncompleteTarget>,
+    unused_factories: Vec<usize>,
+    failed_injections: Vec<FailedInjection>
 }
 
-impl<'a, T: Clone> Injector<'a, T>
-{
-    pub fn new() -> Self
+impl ValidationError {
+    ///
+    /// Returns all targets that have fields which were not injected.
+    ///
+    pub fn incomplete_targets(&self) -> &[IncompleteTarget] {
+        &self.incomplete_targets
+    }
+
+    ///
+    /// Returns the indices of the factories, in the order they were registered with `inject`,
+    /// whose values were not accepted by any target.
+    ///
+    pub fn unused_factories(&self) -> &[usize] {
+        &self.unused_factories
+    }
+
+    ///
+    /// Returns every value that a fallible factory failed to produce, with the target it was
+    /// meant for.
+    ///
+    pub fn failed_injections(&self) -> &[FailedInjection] {
+        &self.failed_injections
+    }
+
+    fn check<M, C>(missing: M, consumed: C, failures: &[FailedInjection]) -> Result<(), Self>
+        where M: Iterator<Item = (usize, Vec<InjectionPoint>)>, C: Iterator<Item = (usize, bool)>
     {
-        Self {
-            factories: Vec::new(),
-            targets: Vec::new()
+        let incomplete_targets: Vec<_> = missing
+            .map(|(target, missing)| IncompleteTarget { target, missing })
+            .filter(|incomplete| !incomplete.missing.is_empty())
+            .collect();
+
+        let unused_factories: Vec<_> = consumed
+            .filter(|(_, consumed)| !*consumed)
+            .map(|(factory, _)| factory)
+            // failing factories are reported as failures instead
+            .filter(|factory| !failures.iter().any(|failure| failure.scope == 0 && failure.factory == *factory))
+            .collect();
+
+        if incomplete_targets.is_empty() && unused_factories.is_empty() && failures.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(Self { incomplete_targets, unused_factories, failed_injections: failures.to_vec() })
         }
     }
+}
 
-    pub fn inject(&'a mut self, factory: &'a dyn Fn() -> T) -> &'a mut Self
-    {
-        self.factories.push(factory);
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Injection is incomplete")?;
+
+        for incomplete in &self.incomplete_targets {
+            for point in &incomplete.missing {
+                write!(f, "\n  target {}: {} was not injected", incomplete.target, point)?;
+            }
+        }
+
+        for factory in &self.unused_factories {
+            write!(f, "\n  factory {}: no target accepted its value", factory)?;
+        }
 
-        for target in self.targets.iter_mut()
-        {
-            target.inject(factory());
+        for failure in &self.failed_injections {
+            match failure.scope {
+                0 => write!(f, "\n  factory {}: failed for target {}: {}", failure.factory, failure.target, failure.error)?,
+                scope => write!(
+                    f, "\n  factory {} of parent scope {}: failed for target {}: {}",
+                    failure.factory, scope, failure.target, failure.error
+                )?
+            }
         }
 
-        self
+        Ok(())
     }
+}
 
-    pub fn to<Target: Injectable<T>>(&'a mut self, target: &'a mut Target) -> &'a mut Self
-    {
-        for factory in &self.factories
-        {
-            target.inject(factory());
+impl Error for ValidationError {}
+
+#[doc(hidden)]
+pub mod __private {
+    ///
+    /// Ties an injected field's type to the payload of its enum member. The code generated by
+    /// `#[injectable]` passes every payload through this trait, so mismatches are reported at the
+    /// field instead of inside the generated code.
+    ///
+    #[diagnostic::on_unimplemented(
+        message = "the field type `{F}` does not match the enum member's payload `{Self}`",
+        label = "this field is injected with a `{Self}`",
+        note = "a field marked with #[inject(Enum::Member)] must be of type `Injected<P>`, where `P` is the type of the member's value"
+    )]
+    pub trait Payload<F> {
+        fn into_field(self) -> F;
+
+        fn from_field(field: F) -> Self;
+    }
+
+    impl<F> Payload<F> for F {
+        fn into_field(self) -> F {
+            self
+        }
+
+        fn from_field(field: F) -> Self {
+            field
         }
+    }
 
-        self.targets.push(target);
+    ///
+    /// Converts between a payload and its field type in both directions. Generated code gets it
+    /// from `payload` with the same span everywhere, so a mismatch is reported only once.
+    ///
+    pub struct Cast<P, F> {
+        pub into_field: fn(P) -> F,
+        pub from_field: fn(F) -> P
+    }
 
-        self
+    pub fn payload<P: Payload<F>, F>() -> Cast<P, F> {
+        Cast { into_field: P::into_field, from_field: P::from_field }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,201 @@
+use std::cell::{Cell, Ref, RefCell};
+use std::fmt::{Debug, Formatter};
+use std::rc::{Rc, Weak};
+
+use crate::{Injected, NotInjected};
+
+///
+/// Observable is a shared, mutable model that notifies its subscribers whenever it is changed. It
+/// can be used as an enum payload instead of an `Rc<RefCell<_>>`: clones share the same value and
+/// subscribers, so every struct it is injected into observes the same model.
+///
+/// Subscribers are called after every `set` or `update`, while the value is borrowed. They can
+/// read the value, but changing it from a subscriber panics.
+///
+pub struct Observable<T>
+{
+    inner: Rc<Shared<T>>
+}
+
+struct Shared<T>
+{
+    value: RefCell<T>,
+    subscribers: RefCell<Vec<(usize, Subscriber<T>)>>,
+    next_id: Cell<usize>
+}
+
+type Subscriber<T> = Rc<dyn Fn(&T)>;
+
+trait Unsubscribe
+{
+    fn unsubscribe(&self, id: usize);
+}
+
+impl<T> Unsubscribe for Shared<T>
+{
+    fn unsubscribe(&self, id: usize)
+    {
+        self.subscribers.borrow_mut().retain(|(other, _)| *other != id);
+    }
+}
+
+impl<T> Observable<T>
+{
+    pub fn new(value: T) -> Self
+    {
+        Self {
+            inner: Rc::new(Shared {
+                value: RefCell::new(value),
+                subscribers: RefCell::new(Vec::new()),
+                next_id: Cell::new(0)
+            })
+        }
+    }
+
+    ///
+    /// Borrows the current value.
+    ///
+    pub fn get(&self) -> Ref<'_, T>
+    {
+        self.inner.value.borrow()
+    }
+
+    ///
+    /// Replaces the value, and notifies all subscribers.
+    ///
+    pub fn set(&self, value: T)
+    {
+        *self.inner.value.borrow_mut() = value;
+        self.notify();
+    }
+
+    ///
+    /// Changes the value in place, and notifies all subscribers.
+    ///
+    pub fn update<F: FnOnce(&mut T)>(&self, update: F)
+    {
+        update(&mut self.inner.value.borrow_mut());
+        self.notify();
+    }
+
+    ///
+    /// Returns true if both observables share the same value.
+    ///
+    pub fn ptr_eq(&self, other: &Self) -> bool
+    {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    fn notify(&self)
+    {
+        // subscribers may subscribe or unsubscribe while being notified
+        let subscribers: Vec<_> = self.inner.subscribers.borrow().iter()
+            .map(|(_, subscriber)| Rc::clone(subscriber))
+            .collect();
+        let value = self.inner.value.borrow();
+
+        for subscriber in subscribers
+        {
+            subscriber(&value);
+        }
+    }
+}
+
+impl<T: 'static> Observable<T>
+{
+    ///
+    /// Calls `subscriber` with the new value whenever the value changes, until the returned
+    /// Subscription is dropped.
+    ///
+    pub fn subscribe<F: Fn(&T) + 'static>(&self, subscriber: F) -> Subscription
+    {
+        let id = self.inner.next_id.get();
+        self.inner.next_id.set(id + 1);
+        self.inner.subscribers.borrow_mut().push((id, Rc::new(subscriber)));
+
+        let shared: Rc<dyn Unsubscribe> = self.inner.clone();
+
+        Subscription {
+            shared: Rc::downgrade(&shared),
+            id
+        }
+    }
+}
+
+impl<T> Clone for Observable<T>
+{
+    fn clone(&self) -> Self
+    {
+        Self {
+            inner: Rc::clone(&self.inner)
+        }
+    }
+}
+
+impl<T: Default> Default for Observable<T>
+{
+    fn default() -> Self
+    {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Debug> Debug for Observable<T>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result
+    {
+        f.debug_tuple("Observable").field(&*self.get()).finish()
+    }
+}
+
+///
+/// Keeps a subscriber of an Observable alive. The subscriber is removed when this is dropped.
+///
+#[must_use = "the subscriber is removed as soon as the subscription is dropped"]
+pub struct Subscription
+{
+    shared: Weak<dyn Unsubscribe>,
+    id: usize
+}
+
+impl Subscription
+{
+    ///
+    /// Keeps the subscriber for as long as the observable exists.
+    ///
+    pub fn detach(self)
+    {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for Subscription
+{
+    fn drop(&mut self)
+    {
+        if let Some(shared) = self.shared.upgrade()
+        {
+            shared.unsubscribe(self.id);
+        }
+    }
+}
+
+impl Debug for Subscription
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result
+    {
+        f.debug_struct("Subscription").field("id", &self.id).finish()
+    }
+}
+
+impl<T: 'static> Injected<Observable<T>>
+{
+    ///
+    /// Subscribes to the injected observable, or returns a NotInjected error if nothing was
+    /// injected yet. See `Observable::subscribe`.
+    ///
+    pub fn try_subscribe<F: Fn(&T) + 'static>(&self, subscriber: F) -> Result<Subscription, NotInjected>
+    {
+        self.try_get().map(|observable| observable.subscribe(subscriber))
+    }
+}
@@ -0,0 +1,150 @@
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::{Injectable, Keyed};
+
+///
+/// A type-erased value, as injected into structs marked with `#[injectable]` without a model enum.
+/// Fields receive a clone of the value if their type matches.
+///
+#[derive(Clone)]
+pub struct Binding
+{
+    value: Rc<dyn Any>,
+    type_name: &'static str
+}
+
+impl Binding
+{
+    ///
+    /// Creates a new Binding for the given value.
+    ///
+    pub fn new<T: Clone + 'static>(value: T) -> Self
+    {
+        Self {
+            value: Rc::new(value),
+            type_name: type_name::<T>()
+        }
+    }
+
+    ///
+    /// Returns the TypeId of the bound value.
+    ///
+    pub fn value_type(&self) -> TypeId
+    {
+        (*self.value).type_id()
+    }
+
+    ///
+    /// Returns the name of the bound value's type.
+    ///
+    pub fn type_name(&self) -> &'static str
+    {
+        self.type_name
+    }
+
+    ///
+    /// Returns true if the bound value is of type T.
+    ///
+    pub fn is<T: 'static>(&self) -> bool
+    {
+        self.value.is::<T>()
+    }
+
+    ///
+    /// Returns a clone of the bound value if it is of type T.
+    ///
+    pub fn get<T: Clone + 'static>(&self) -> Option<T>
+    {
+        self.value.downcast_ref::<T>().cloned()
+    }
+}
+
+impl Keyed for Binding
+{
+    type Key = TypeId;
+
+    fn key(&self) -> TypeId
+    {
+        self.value_type()
+    }
+}
+
+///
+/// Registry holds factories keyed by the type they produce. It takes the place of the model enum,
+/// so separate crates can each contribute bindings without sharing a central enum.
+///
+pub struct Registry
+{
+    factories: HashMap<TypeId, Box<dyn Fn() -> Binding>>
+}
+
+impl Registry
+{
+    pub fn new() -> Self
+    {
+        Self {
+            factories: HashMap::new()
+        }
+    }
+
+    ///
+    /// Registers a factory for values of type T. This replaces any factory that was previously
+    /// registered for the same type.
+    ///
+    pub fn bind<T, F>(&mut self, factory: F) -> &mut Self
+        where T: Clone + 'static, F: Fn() -> T + 'static
+    {
+        self.factories.insert(TypeId::of::<T>(), Box::new(move || Binding::new(factory())));
+        self
+    }
+
+    ///
+    /// Moves all factories from another registry into this one. Factories from `other` replace
+    /// existing factories for the same type.
+    ///
+    pub fn extend(&mut self, other: Registry) -> &mut Self
+    {
+        self.factories.extend(other.factories);
+        self
+    }
+
+    ///
+    /// Returns true if a factory is registered for type T.
+    ///
+    pub fn contains<T: 'static>(&self) -> bool
+    {
+        self.factories.contains_key(&TypeId::of::<T>())
+    }
+
+    ///
+    /// Creates a value of type T using its registered factory, if any.
+    ///
+    pub fn resolve<T: Clone + 'static>(&self) -> Option<T>
+    {
+        let factory = self.factories.get(&TypeId::of::<T>())?;
+        factory().get()
+    }
+
+    ///
+    /// Injects a value from every registered factory into the target.
+    ///
+    pub fn inject_into<Target: Injectable<Binding> + ?Sized>(&self, target: &mut Target)
+    {
+        target.describe_fields();
+
+        for factory in self.factories.values()
+        {
+            target.inject(factory());
+        }
+    }
+}
+
+impl Default for Registry
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
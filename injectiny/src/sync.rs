@@ -0,0 +1,266 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, ThreadId};
+
+use crate::injector::FactoryKey;
+use crate::{Keyed, Target, ValidationError};
+
+///
+/// SyncInjector is an injector that can be shared between threads, for instance through an `Arc`.
+/// Its factories must be `Send + Sync`, and its targets are shared handles that can be sent to
+/// other threads, such as `Arc<Mutex<_>>` or `Arc<RwLock<_>>`. All methods take `&self`: factories
+/// and targets can be registered from any thread, and each injection locks the target it injects
+/// into. Targets must not be locked by the registering thread while it calls into the injector.
+///
+/// Values are injected by one thread at a time, and only if their factory is still registered,
+/// so a value from a replaced factory never lands after its replacement's. Factories can register
+/// further factories and targets. So can `on_inject` hooks, but their registrations are only
+/// injected once the current injection has finished; hooks must not call `validate`, which locks
+/// every target. As with `Injector`, every factory is injected into every target, and a factory
+/// bound to a key with `bind` replaces the factory that was bound to it before.
+///
+pub struct SyncInjector<T: Clone>
+{
+    state: Mutex<State<T>>,
+    delivery: Mutex<()>
+}
+
+type SyncFactory<T> = Arc<dyn Fn() -> T + Send + Sync>;
+
+struct State<T: Clone>
+{
+    factories: Vec<SyncProvider<T>>,
+    targets: Vec<Box<dyn SyncTarget<T>>>,
+    next_factory: usize,
+    delivering: Option<ThreadId>,
+    pending: Vec<Delivery<T>>
+}
+
+struct SyncProvider<T>
+{
+    index: usize,
+    factory: SyncFactory<T>,
+    key: Option<Box<dyn FactoryKey + Send + Sync>>,
+    consumed: bool
+}
+
+///
+/// A value that still has to be produced by the factory at `index` and injected into `target`.
+///
+struct Delivery<T: Clone>
+{
+    index: usize,
+    factory: SyncFactory<T>,
+    target: Box<dyn SyncTarget<T>>
+}
+
+///
+/// A target handle that can be cloned, so it can be injected into after the state lock is released.
+///
+trait SyncTarget<T: Clone>: Target<T> + Send
+{
+    fn clone_target(&self) -> Box<dyn SyncTarget<T>>;
+}
+
+impl<T: Clone, R: Target<T> + Clone + Send + 'static> SyncTarget<T> for R
+{
+    fn clone_target(&self) -> Box<dyn SyncTarget<T>>
+    {
+        Box::new(self.clone())
+    }
+}
+
+impl<T: Clone> SyncInjector<T>
+{
+    pub fn new() -> Self
+    {
+        Self {
+            state: Mutex::new(State {
+                factories: Vec::new(),
+                targets: Vec::new(),
+                next_factory: 0,
+                delivering: None,
+                pending: Vec::new()
+            }),
+            delivery: Mutex::new(())
+        }
+    }
+
+    ///
+    /// Registers a factory, and injects its value into every registered target.
+    ///
+    pub fn inject<F: Fn() -> T + Send + Sync + 'static>(&self, factory: F) -> &Self
+    {
+        self.register(None, Arc::new(factory))
+    }
+
+    ///
+    /// Registers a factory for the member identified by `key`, and injects its value into every
+    /// registered target. A factory that was bound to the same key before is replaced. See
+    /// `Injector::bind`.
+    ///
+    pub fn bind<F: Fn() -> T + Send + Sync + 'static>(&self, key: T::Key, factory: F) -> &Self
+        where T: Keyed, T::Key: Send + Sync
+    {
+        self.register(Some(Box::new(key)), Arc::new(factory))
+    }
+
+    ///
+    /// Registers a target, and injects the values of all registered factories into it.
+    ///
+    pub fn to<R: Target<T> + Clone + Send + 'static>(&self, mut target: R) -> &Self
+    {
+        target.describe_fields();
+
+        let deliveries: Vec<_> = {
+            let mut state = self.lock();
+            state.targets.push(Box::new(target.clone()));
+            state.factories.iter().map(|provider| Delivery {
+                index: provider.index,
+                factory: Arc::clone(&provider.factory),
+                target: target.clone_target()
+            }).collect()
+        };
+
+        self.deliver(deliveries)
+    }
+
+    ///
+    /// Checks that every registered target has been fully injected, and that every factory has
+    /// been accepted by at least one target. See `Injector::validate`.
+    ///
+    pub fn validate(&self) -> Result<(), ValidationError>
+    {
+        let (targets, consumed) = {
+            let state = self.lock();
+            let targets: Vec<_> = state.targets.iter().map(|target| target.clone_target()).collect();
+            let consumed: Vec<_> = state.factories.iter().map(|provider| (provider.index, provider.consumed)).collect();
+            (targets, consumed)
+        };
+
+        ValidationError::check(
+            targets.iter().map(|target| target.missing_injections()).enumerate(),
+            consumed.into_iter(),
+            &[]
+        )
+    }
+
+    fn register(&self, key: Option<Box<dyn FactoryKey + Send + Sync>>, factory: SyncFactory<T>) -> &Self
+    {
+        let deliveries: Vec<_> = {
+            let mut state = self.lock();
+            let index = state.next_factory;
+            state.next_factory += 1;
+
+            let provider = SyncProvider { index, factory: Arc::clone(&factory), key, consumed: false };
+            let replaced = provider.key.as_deref().and_then(|key| {
+                state.factories.iter().position(|other| other.key.as_deref().is_some_and(|other| other.equals(key)))
+            });
+
+            match replaced {
+                Some(position) => state.factories[position] = provider,
+                None => state.factories.push(provider)
+            }
+
+            state.targets.iter().map(|target| Delivery {
+                index,
+                factory: Arc::clone(&factory),
+                target: target.clone_target()
+            }).collect()
+        };
+
+        self.deliver(deliveries)
+    }
+
+    ///
+    /// Produces and injects the given values. Registrations made while this thread is already
+    /// injecting, from an `on_inject` hook, are queued and injected before that injection returns.
+    ///
+    fn deliver(&self, deliveries: Vec<Delivery<T>>) -> &Self
+    {
+        {
+            let mut state = self.lock();
+            if state.delivering == Some(thread::current().id())
+            {
+                state.pending.extend(deliveries);
+                return self;
+            }
+        }
+
+        // the factories run before the delivery lock is taken, so they can call the injector
+        let mut values: Vec<_> = deliveries.into_iter().map(|delivery| ((delivery.factory)(), delivery)).collect();
+
+        let _delivery = self.delivery.lock().unwrap_or_else(PoisonError::into_inner);
+        let _delivering = Delivering::start(self);
+
+        while !values.is_empty()
+        {
+            for (value, mut delivery) in values
+            {
+                if self.is_registered(delivery.index) && delivery.target.inject(value)
+                {
+                    self.consume(delivery.index);
+                }
+            }
+
+            let pending = std::mem::take(&mut self.lock().pending);
+            values = pending.into_iter().map(|delivery| ((delivery.factory)(), delivery)).collect();
+        }
+
+        self
+    }
+
+    fn is_registered(&self, index: usize) -> bool
+    {
+        // a replaced factory's value must not overwrite its replacement's
+        self.lock().factories.iter().any(|provider| provider.index == index)
+    }
+
+    fn consume(&self, index: usize)
+    {
+        if let Some(provider) = self.lock().factories.iter_mut().find(|provider| provider.index == index)
+        {
+            provider.consumed = true;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>>
+    {
+        // a panicking factory or target leaves the registrations themselves intact
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+///
+/// Marks the current thread as injecting until it is dropped, also when a factory or target panics.
+///
+struct Delivering<'a, T: Clone>
+{
+    injector: &'a SyncInjector<T>
+}
+
+impl<'a, T: Clone> Delivering<'a, T>
+{
+    fn start(injector: &'a SyncInjector<T>) -> Self
+    {
+        injector.lock().delivering = Some(thread::current().id());
+        Self { injector }
+    }
+}
+
+impl<T: Clone> Drop for Delivering<'_, T>
+{
+    fn drop(&mut self)
+    {
+        let mut state = self.injector.lock();
+        state.delivering = None;
+        state.pending.clear();
+    }
+}
+
+impl<T: Clone> Default for SyncInjector<T>
+{
+    fn default() -> Self
+    {
+        Self::new()
+    }
+}
@@ -0,0 +1,133 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+use crate::{Injectable, InjectionPoint};
+
+///
+/// Target is a handle to an injectable struct that an injector can hold on to. It is implemented
+/// for mutable references, as well as for structs shared through `Rc<RefCell<_>>`,
+/// `Arc<Mutex<_>>` or `Arc<RwLock<_>>`. Shared targets must not be borrowed or locked elsewhere
+/// while the injector is injecting into them.
+///
+pub trait Target<T: Clone> {
+    ///
+    /// Injects the value into the target, and returns true if the target accepted it.
+    ///
+    fn inject(&mut self, value: T) -> bool;
+
+    ///
+    /// Returns the injection points of all fields of the target that have not been injected yet.
+    ///
+    fn missing_injections(&self) -> Vec<InjectionPoint>;
+
+    ///
+    /// Records where each field of the target is injected. See `Injectable::describe_fields`.
+    ///
+    fn describe_fields(&mut self);
+
+    ///
+    /// Clears the fields of the target whose values are matched by `matches`, and returns true if
+    /// any field was cleared. See `Injectable::uninject_matching`.
+    ///
+    fn uninject_matching(&mut self, matches: &mut dyn FnMut(&T) -> bool) -> bool;
+}
+
+fn inject_into<T: Clone, I: Injectable<T> + ?Sized>(target: &mut I, value: T) -> bool
+{
+    let accepted = target.accepts(&value);
+    target.inject(value);
+    accepted
+}
+
+impl<T: Clone, I: Injectable<T> + ?Sized> Target<T> for &mut I
+{
+    fn inject(&mut self, value: T) -> bool
+    {
+        inject_into(&mut **self, value)
+    }
+
+    fn missing_injections(&self) -> Vec<InjectionPoint>
+    {
+        (**self).missing_injections()
+    }
+
+    fn describe_fields(&mut self)
+    {
+        (**self).describe_fields();
+    }
+
+    fn uninject_matching(&mut self, matches: &mut dyn FnMut(&T) -> bool) -> bool
+    {
+        (**self).uninject_matching(matches)
+    }
+}
+
+impl<T: Clone, I: Injectable<T> + ?Sized> Target<T> for Rc<RefCell<I>>
+{
+    fn inject(&mut self, value: T) -> bool
+    {
+        inject_into(&mut *self.borrow_mut(), value)
+    }
+
+    fn missing_injections(&self) -> Vec<InjectionPoint>
+    {
+        self.borrow().missing_injections()
+    }
+
+    fn describe_fields(&mut self)
+    {
+        self.borrow_mut().describe_fields();
+    }
+
+    fn uninject_matching(&mut self, matches: &mut dyn FnMut(&T) -> bool) -> bool
+    {
+        self.borrow_mut().uninject_matching(matches)
+    }
+}
+
+impl<T: Clone, I: Injectable<T> + ?Sized> Target<T> for Arc<Mutex<I>>
+{
+    fn inject(&mut self, value: T) -> bool
+    {
+        inject_into(&mut *self.lock().unwrap_or_else(PoisonError::into_inner), value)
+    }
+
+    fn missing_injections(&self) -> Vec<InjectionPoint>
+    {
+        self.lock().unwrap_or_else(PoisonError::into_inner).missing_injections()
+    }
+
+    fn describe_fields(&mut self)
+    {
+        self.lock().unwrap_or_else(PoisonError::into_inner).describe_fields();
+    }
+
+    fn uninject_matching(&mut self, matches: &mut dyn FnMut(&T) -> bool) -> bool
+    {
+        self.lock().unwrap_or_else(PoisonError::into_inner).uninject_matching(matches)
+    }
+}
+
+impl<T: Clone, I: Injectable<T> + ?Sized> Target<T> for Arc<RwLock<I>>
+{
+    fn inject(&mut self, value: T) -> bool
+    {
+        inject_into(&mut *self.write().unwrap_or_else(PoisonError::into_inner), value)
+    }
+
+    fn missing_injections(&self) -> Vec<InjectionPoint>
+    {
+        self.read().unwrap_or_else(PoisonError::into_inner).missing_injections()
+    }
+
+    fn describe_fields(&mut self)
+    {
+        self.write().unwrap_or_else(PoisonError::into_inner).describe_fields();
+    }
+
+    fn uninject_matching(&mut self, matches: &mut dyn FnMut(&T) -> bool) -> bool
+    {
+        self.write().unwrap_or_else(PoisonError::into_inner).uninject_matching(matches)
+    }
+}
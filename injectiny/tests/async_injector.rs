@@ -0,0 +1,47 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+
+use injectiny::{injectable, AsyncInjector, Injected};
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Model {
+    Age(u32)
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Ages {
+    #[inject(Model::Age, collect)]
+    ages: Injected<Vec<u32>>
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn injecting_again_does_not_collect_twice() {
+    let target: Rc<RefCell<Ages>> = Default::default();
+
+    let mut injector = AsyncInjector::new();
+    injector
+        .inject(|| async { Model::Age(1) })
+        .inject(|| async { Model::Age(2) })
+        .to(Rc::clone(&target));
+
+    block_on(injector.inject_all());
+    block_on(injector.inject_all());
+
+    assert_eq!(*target.borrow().ages, vec![1, 2]);
+    assert!(injector.validate().is_ok());
+}
@@ -0,0 +1,91 @@
+use std::cell::Cell;
+
+use injectiny::{injectable, Construct, Injected, Injector, Lifetime};
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Model {
+    Name(String),
+    Count(u32)
+}
+
+#[derive(Construct)]
+#[construct(Model)]
+struct Report {
+    #[inject(Model::Count)]
+    missing: u32,
+
+    #[inject]
+    resolver: String
+}
+
+#[test]
+fn field_names_do_not_clash_with_generated_code() {
+    let mut injector = Injector::new();
+    injector
+        .inject(|| Model::Name("Patje".to_string()))
+        .inject(|| Model::Count(3));
+
+    let report = Report::construct(&injector).unwrap();
+    assert_eq!(report.missing, 3);
+    assert_eq!(report.resolver, "Patje");
+}
+
+#[test]
+fn factories_bound_to_other_members_are_not_called() {
+    let calls = Cell::new(0);
+
+    let mut injector = Injector::new();
+    injector.bind(ModelKey::Count, Lifetime::Transient, || {
+        calls.set(calls.get() + 1);
+        Model::Count(3)
+    });
+    injector.bind(ModelKey::Name, Lifetime::Transient, || Model::Name("Patje".to_string()));
+
+    Report::construct(&injector).unwrap();
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn failing_factories_are_reported() {
+    let mut injector = Injector::new();
+    injector.inject(|| Model::Name("Patje".to_string()));
+    injector.try_bind(ModelKey::Count, Lifetime::Transient, || "many".parse().map(Model::Count));
+
+    let missing = Report::construct(&injector).err().unwrap();
+    assert_eq!(missing.points().len(), 1);
+    assert_eq!(missing.failures().len(), 1);
+    assert_eq!(missing.failures()[0].0.field, "missing");
+    assert!(missing.to_string().contains("invalid digit"));
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Named {
+    #[inject]
+    name: Injected<String>
+}
+
+#[derive(Construct)]
+#[construct(Model)]
+struct Constructed {
+    #[inject]
+    name: String
+}
+
+#[test]
+fn the_last_factory_wins_as_with_field_injection() {
+    let mut named = Named::default();
+
+    let mut injector = Injector::new();
+    injector
+        .inject(|| Model::Name("first".to_string()))
+        .inject(|| Model::Name("second".to_string()));
+
+    let constructed = Constructed::construct(&injector).unwrap();
+    injector.to(&mut named);
+    drop(injector);
+
+    assert_eq!(*named.name, "second");
+    assert_eq!(constructed.name, "second");
+}
@@ -0,0 +1,279 @@
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use injectiny::{injectable, Binding, Injected, Injector, Lifetime, SharedInjector};
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Model {
+    Name(String),
+    Age(u32)
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Person {
+    #[inject]
+    name: Injected<String>,
+
+    #[inject]
+    age: Injected<u32>
+}
+
+#[injectable]
+#[derive(Default)]
+struct Typed {
+    #[inject]
+    name: Injected<String>,
+
+    #[inject]
+    age: Injected<u32>
+}
+
+#[test]
+fn bindings_of_different_types_are_all_injected() {
+    let target: Rc<RefCell<Typed>> = Default::default();
+
+    let mut injector = Injector::new();
+    injector
+        .inject(|| Binding::new("Patje".to_string()))
+        .inject(|| Binding::new(25u32))
+        .to(Rc::clone(&target));
+
+    assert_eq!(*target.borrow().name, "Patje");
+    assert_eq!(*target.borrow().age, 25);
+    assert!(injector.validate().is_ok());
+}
+
+#[test]
+fn bindings_are_keyed_by_type() {
+    let target: Rc<RefCell<Typed>> = Default::default();
+
+    let mut injector = Injector::new();
+    injector.bind(TypeId::of::<String>(), Lifetime::Transient, || Binding::new("Patje".to_string()));
+    injector.bind(TypeId::of::<u32>(), Lifetime::Transient, || Binding::new(25u32));
+    injector.to(Rc::clone(&target));
+
+    assert!(injector.rebind(TypeId::of::<String>(), Lifetime::Transient, || Binding::new("Patje Jr.".to_string())));
+    assert_eq!(*target.borrow().name, "Patje Jr.");
+    assert_eq!(*target.borrow().age, 25);
+}
+
+#[test]
+fn factories_are_called_once_per_target() {
+    let calls = Cell::new(0);
+
+    let mut injector = Injector::new();
+    injector.inject(|| {
+        calls.set(calls.get() + 1);
+        Model::Name("Patje".to_string())
+    });
+    injector.bind(ModelKey::Age, Lifetime::Transient, || {
+        calls.set(calls.get() + 1);
+        Model::Age(25)
+    });
+
+    // nothing needs the values yet
+    assert_eq!(calls.get(), 0);
+
+    let first: Rc<RefCell<Person>> = Default::default();
+    let second: Rc<RefCell<Person>> = Default::default();
+    injector.to(Rc::clone(&first)).to(Rc::clone(&second));
+
+    assert_eq!(calls.get(), 4);
+    assert_eq!(*second.borrow().name, "Patje");
+    assert_eq!(*second.borrow().age, 25);
+}
+
+#[test]
+fn failing_bound_factory_can_be_replaced() {
+    let target: Rc<RefCell<Person>> = Default::default();
+
+    let mut injector = Injector::new();
+    injector.inject(|| Model::Name("Patje".to_string()));
+    injector.try_bind(ModelKey::Age, Lifetime::Transient, || "old".parse().map(Model::Age));
+    injector.to(Rc::clone(&target));
+
+    assert_eq!(injector.validate().unwrap_err().failed_injections().len(), 1);
+
+    assert!(injector.rebind(ModelKey::Age, Lifetime::Transient, || Model::Age(25)));
+    assert_eq!(*target.borrow().age, 25);
+    assert!(injector.validate().is_ok());
+}
+
+#[test]
+fn replaced_factory_handle_is_stale() {
+    let injector = Rc::new(RefCell::new(SharedInjector::new()));
+
+    let first = injector.borrow_mut().bind(ModelKey::Name, Lifetime::Transient, || Model::Name("Patje".to_string()));
+    let first = first.guard(&injector);
+
+    let second = injector.borrow_mut().bind(ModelKey::Name, Lifetime::Transient, || Model::Name("Patje Jr.".to_string()));
+    let second = second.guard(&injector);
+
+    // dropping the guard of the replaced factory leaves its replacement in place
+    drop(first);
+
+    let target: Rc<RefCell<Person>> = Default::default();
+    injector.borrow_mut().to(Rc::clone(&target));
+    assert_eq!(*target.borrow().name, "Patje Jr.");
+
+    drop(second);
+    injector.borrow_mut().inject(|| Model::Age(25));
+    assert!(injector.borrow().validate().is_ok());
+}
+
+#[test]
+fn shadowed_parent_factories_are_not_called() {
+    let calls = Cell::new(0);
+
+    let mut root = Injector::new();
+    root.inject(|| Model::Name("Patje".to_string()));
+    root.bind(ModelKey::Age, Lifetime::Transient, || {
+        calls.set(calls.get() + 1);
+        Model::Age(25)
+    });
+
+    let grandchild_target: Rc<RefCell<Person>> = Default::default();
+    let child_target: Rc<RefCell<Person>> = Default::default();
+
+    let mut child = root.child();
+    child.bind(ModelKey::Age, Lifetime::Transient, || Model::Age(7));
+    child.to(Rc::clone(&child_target));
+
+    let mut grandchild = child.child();
+    grandchild.inject(|| Model::Name("Patje Jr.".to_string()));
+    grandchild.to(Rc::clone(&grandchild_target));
+
+    assert_eq!(calls.get(), 0);
+    assert_eq!(*child_target.borrow().age, 7);
+    assert_eq!(*grandchild_target.borrow().age, 7);
+
+    // unbound values of nearer scopes are injected last
+    assert_eq!(*child_target.borrow().name, "Patje");
+    assert_eq!(*grandchild_target.borrow().name, "Patje Jr.");
+}
+
+#[injectable(crate::Model)]
+#[derive(Default)]
+struct Registry {
+    #[inject(crate::Model::Name)]
+    name: Injected<String>,
+
+    #[inject(crate::Model::Age, collect)]
+    ages: Injected<Vec<u32>>
+}
+
+#[test]
+fn uninject_clears_fields_by_key() {
+    let target: Rc<RefCell<Registry>> = Default::default();
+
+    let mut injector = Injector::new();
+    injector
+        .inject(|| Model::Name("Patje".to_string()))
+        .inject(|| Model::Age(25))
+        .inject(|| Model::Age(7))
+        .to(Rc::clone(&target));
+
+    assert_eq!(*target.borrow().ages, vec![25, 7]);
+
+    assert!(injector.uninject(ModelKey::Name));
+    assert!(!target.borrow().name.is_injected());
+    assert!(!injector.uninject(ModelKey::Name));
+
+    assert!(injector.uninject(ModelKey::Age));
+    assert!(!target.borrow().ages.is_injected());
+}
+
+#[test]
+fn uninject_bindings_by_type() {
+    let target: Rc<RefCell<Typed>> = Default::default();
+
+    let mut injector = Injector::new();
+    injector
+        .inject(|| Binding::new("Patje".to_string()))
+        .inject(|| Binding::new(25u32))
+        .to(Rc::clone(&target));
+
+    assert!(injector.uninject(TypeId::of::<String>()));
+    assert!(!target.borrow().name.is_injected());
+    assert_eq!(*target.borrow().age, 25);
+}
+
+#[test]
+fn injection_points_survive_injection() {
+    let target: Rc<RefCell<Person>> = Default::default();
+
+    let mut injector = Injector::new();
+    injector.inject(|| Model::Name("Patje".to_string())).to(Rc::clone(&target));
+
+    target.borrow_mut().name.take();
+    let error = target.borrow().name.try_get().unwrap_err();
+    assert_eq!(error.point().unwrap().field, "name");
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Named {
+    #[inject]
+    name: Injected<String>
+}
+
+#[test]
+fn inherited_failures_are_kept_apart_from_own_factories() {
+    let mut root = Injector::new();
+    root.try_inject(|| "many".parse().map(Model::Age));
+
+    let mut child = root.child();
+    let unused = child.add_factory(Lifetime::Transient, || Model::Age(25));
+    child.to(Rc::new(RefCell::new(Named::default())));
+
+    // the child's own factory 0 is not the parent's factory 0
+    let error = child.validate().unwrap_err();
+    assert_eq!(error.unused_factories(), &[0]);
+    assert_eq!(error.failed_injections().len(), 1);
+    assert_eq!(error.failed_injections()[0].scope(), 1);
+    assert_eq!(error.failed_injections()[0].factory(), 0);
+
+    assert!(child.remove_factory(unused));
+    let error = child.validate().unwrap_err();
+    assert_eq!(error.failed_injections().len(), 1);
+}
+
+#[test]
+fn bound_factories_must_produce_their_member() {
+    let target: Rc<RefCell<Person>> = Default::default();
+
+    let mut injector = Injector::new();
+    injector.bind(ModelKey::Age, Lifetime::Transient, || Model::Name("Patje".to_string()));
+    injector.to(Rc::clone(&target));
+
+    assert!(!target.borrow().name.is_injected());
+    let error = injector.validate().unwrap_err();
+    assert_eq!(error.failed_injections()[0].error().to_string(), "factory bound to Age produced Name");
+
+    // the factory isn't registered under the key of the member it produced
+    assert!(!injector.rebind(ModelKey::Name, Lifetime::Transient, || Model::Name("Patje".to_string())));
+    assert!(injector.rebind(ModelKey::Age, Lifetime::Transient, || Model::Age(25)));
+    assert!(injector.validate().is_ok());
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Ages {
+    #[inject(Model::Age, collect)]
+    ages: Injected<Vec<u32>>
+}
+
+#[test]
+fn rebinding_replaces_collected_values() {
+    let target: Rc<RefCell<Ages>> = Default::default();
+
+    let mut injector = Injector::new();
+    injector.bind(ModelKey::Age, Lifetime::Transient, || Model::Age(1));
+    injector.to(Rc::clone(&target));
+
+    assert!(injector.rebind(ModelKey::Age, Lifetime::Transient, || Model::Age(2)));
+    assert_eq!(*target.borrow().ages, vec![2]);
+}
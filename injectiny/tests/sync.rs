@@ -0,0 +1,107 @@
+use std::sync::{Arc, Mutex, Weak};
+
+use injectiny::{injectable, Injected, SyncInjector};
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Model {
+    Name(String),
+    Age(u32)
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Person {
+    #[inject]
+    name: Injected<String>,
+
+    #[inject]
+    age: Injected<u32>
+}
+
+#[test]
+fn factories_can_call_the_injector() {
+    let target: Arc<Mutex<Person>> = Default::default();
+
+    let injector = Arc::new(SyncInjector::new());
+    injector.to(Arc::clone(&target));
+
+    let weak = Arc::downgrade(&injector);
+    injector.inject(move || {
+        let injector = weak.upgrade().unwrap();
+        assert!(injector.validate().is_err());
+        injector.inject(|| Model::Name("Patje".to_string()));
+        Model::Age(25)
+    });
+
+    assert_eq!(*target.lock().unwrap().name, "Patje");
+    assert_eq!(*target.lock().unwrap().age, 25);
+    assert!(injector.validate().is_ok());
+}
+
+#[test]
+fn bound_factories_are_replaced() {
+    let target: Arc<Mutex<Person>> = Default::default();
+
+    let injector = SyncInjector::new();
+    injector
+        .inject(|| Model::Name("Patje".to_string()))
+        .bind(ModelKey::Age, || Model::Age(25))
+        .to(Arc::clone(&target))
+        .bind(ModelKey::Age, || Model::Age(26));
+
+    assert_eq!(*target.lock().unwrap().age, 26);
+
+    // the replaced factory is no longer injected into new targets
+    let other: Arc<Mutex<Person>> = Default::default();
+    injector.to(Arc::clone(&other));
+    assert_eq!(*other.lock().unwrap().age, 26);
+    assert!(injector.validate().is_ok());
+}
+
+#[test]
+fn values_of_replaced_factories_are_not_injected() {
+    let target: Arc<Mutex<Person>> = Default::default();
+
+    let injector = Arc::new(SyncInjector::new());
+    injector.to(Arc::clone(&target));
+
+    let weak = Arc::downgrade(&injector);
+    injector.bind(ModelKey::Age, move || {
+        weak.upgrade().unwrap().bind(ModelKey::Age, || Model::Age(26));
+        Model::Age(25)
+    });
+
+    assert_eq!(*target.lock().unwrap().age, 26);
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Greeter {
+    #[inject(Model::Name, on_inject = "name_changed")]
+    name: Injected<String>,
+
+    #[inject(Model::Age)]
+    age: Injected<u32>,
+
+    injector: Weak<SyncInjector<Model>>
+}
+
+impl Greeter {
+    fn name_changed(&mut self, _old: &Option<String>, _new: &String) {
+        self.injector.upgrade().unwrap().inject(|| Model::Age(25));
+    }
+}
+
+#[test]
+fn hooks_can_register_factories() {
+    let injector = Arc::new(SyncInjector::new());
+    let target = Arc::new(Mutex::new(Greeter { injector: Arc::downgrade(&injector), ..Default::default() }));
+
+    injector
+        .to(Arc::clone(&target))
+        .inject(|| Model::Name("Patje".to_string()));
+
+    assert_eq!(*target.lock().unwrap().age, 25);
+    assert!(injector.validate().is_ok());
+}
@@ -0,0 +1,21 @@
+use injectiny::Injected;
+use injectiny_proc_macro::injectable;
+
+#[derive(Clone)]
+enum Model {
+    Age(u32)
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Injectee {
+    #[inject(Model::Age, collect, on_inject = "age_changed")]
+    ages: Injected<Vec<u32>>
+}
+
+impl Injectee {
+    fn age_changed(&mut self, _old: &Option<u32>, _new: &u32) {}
+}
+
+fn main() {}
+
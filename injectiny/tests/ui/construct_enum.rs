@@ -0,0 +1,15 @@
+use injectiny::Construct;
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Model {
+    Age(u32)
+}
+
+#[derive(Construct)]
+#[construct(Model)]
+enum Injectee {
+    Age(u32)
+}
+
+fn main() {}
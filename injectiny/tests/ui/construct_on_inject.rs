@@ -0,0 +1,16 @@
+use injectiny::Construct;
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Model {
+    Age(u32)
+}
+
+#[derive(Construct)]
+#[construct(Model)]
+struct Injectee {
+    #[inject(Model::Age, on_inject = "age_changed")]
+    age: u32
+}
+
+fn main() {}
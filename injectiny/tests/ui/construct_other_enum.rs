@@ -0,0 +1,25 @@
+use injectiny::Construct;
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Model {
+    Age(u32)
+}
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Other {
+    Name(String)
+}
+
+#[derive(Construct)]
+#[construct(Model)]
+struct Injectee {
+    #[inject(Model::Age)]
+    age: u32,
+
+    #[inject(Other::Name)]
+    name: String
+}
+
+fn main() {}
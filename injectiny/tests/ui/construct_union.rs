@@ -0,0 +1,15 @@
+use injectiny::Construct;
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Model {
+    Age(u32)
+}
+
+#[derive(Construct)]
+#[construct(Model)]
+union Injectee {
+    age: u32
+}
+
+fn main() {}
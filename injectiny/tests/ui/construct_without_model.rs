@@ -0,0 +1,14 @@
+use injectiny::Construct;
+
+#[derive(Clone)]
+enum Model {
+    Age(u32)
+}
+
+#[derive(Construct)]
+struct Injectee {
+    #[inject(Model::Age)]
+    age: u32
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use injectiny::Injected;
+use injectiny_proc_macro::injectable;
+
+#[derive(Clone)]
+enum Model {
+    Age(u32)
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Injectee {
+    #[inject(Model::Age, collect, collect)]
+    ages: Injected<Vec<u32>>
+}
+
+fn main() {}
+
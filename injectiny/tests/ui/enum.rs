@@ -0,0 +1,14 @@
+use injectiny::Injected;
+use injectiny_proc_macro::injectable;
+
+#[derive(Clone)]
+enum Model {
+    Age(u32)
+}
+
+#[injectable(Model)]
+enum Injectee {
+    Age(Injected<u32>)
+}
+
+fn main() {}
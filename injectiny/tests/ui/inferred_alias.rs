@@ -0,0 +1,19 @@
+use injectiny::Injected;
+use injectiny_proc_macro::{injectable, model};
+
+#[model]
+#[derive(Clone)]
+enum Model {
+    Age(u32)
+}
+
+type Age = Injected<u32>;
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Injectee {
+    #[inject]
+    age: Age
+}
+
+fn main() {}
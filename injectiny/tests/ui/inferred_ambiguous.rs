@@ -0,0 +1,18 @@
+use injectiny::Injected;
+use injectiny_proc_macro::{injectable, model};
+
+#[model]
+#[derive(Clone)]
+enum Model {
+    Width(u32),
+    Height(u32)
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Injectee {
+    #[inject]
+    width: Injected<u32>
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use injectiny::Injected;
+use injectiny_proc_macro::injectable;
+
+#[derive(Clone)]
+enum Model {
+    Age(u32)
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Injectee {
+    #[inject(Model::Age, 5)]
+    age: Injected<u32>
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use injectiny::Injected;
+use injectiny_proc_macro::injectable;
+
+#[derive(Clone)]
+enum Model {
+    Age(u32)
+}
+
+#[derive(Clone)]
+enum Other {
+    Name(String)
+}
+
+#[injectable(Model)]
+#[derive(Default)]
+struct Injectee {
+    #[inject(Model::Age)]
+    age: Injected<u32>,
+
+    #[inject(Other::Name)]
+    name: Injected<String>
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use injectiny::model;
+
+type Count = u32;
+
+#[model]
+#[derive(Clone)]
+enum Model {
+    Name(String),
+    Alias(std::string::String),
+    Age(u32),
+    Visits(Count)
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use injectiny_proc_macro::model;
+
+#[model]
+#[derive(Clone)]
+struct Model {
+    age: u32
+}
+
+fn main() {}
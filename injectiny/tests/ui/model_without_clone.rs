@@ -0,0 +1,8 @@
+use injectiny_proc_macro::model;
+
+#[model]
+enum Model {
+    Age(u32)
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use injectiny::Injected;
+use injectiny_proc_macro::injectable;
+
+#[derive(Clone)]
+enum Core {
+    Age(u32)
+}
+
+#[derive(Clone)]
+enum Ui {
+    Title(String)
+}
+
+#[derive(Clone)]
+enum Other {
+    Name(String)
+}
+
+#[injectable(Core, Ui)]
+#[derive(Default)]
+struct Injectee {
+    #[inject(Core::Age)]
+    age: Injected<u32>,
+
+    #[inject(Other::Name)]
+    name: Injected<String>
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use injectiny::Construct;
+
+#[derive(Clone)]
+#[injectiny::model]
+enum Model {
+    Age(u32)
+}
+
+#[derive(Construct)]
+#[construct(Model)]
+struct Ages(#[inject(Model::Age, collect)] Vec<u32>);
+
+fn main() {}
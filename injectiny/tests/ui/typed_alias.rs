@@ -0,0 +1,13 @@
+use injectiny::Injected;
+use injectiny_proc_macro::injectable;
+
+type Age = Injected<u32>;
+
+#[injectable]
+#[derive(Default)]
+struct Injectee {
+    #[inject]
+    age: Age
+}
+
+fn main() {}
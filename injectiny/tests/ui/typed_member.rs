@@ -0,0 +1,16 @@
+use injectiny::Injected;
+use injectiny_proc_macro::injectable;
+
+#[derive(Clone)]
+enum Model {
+    Age(u32)
+}
+
+#[injectable]
+#[derive(Default)]
+struct Injectee {
+    #[inject(Model::Age)]
+    age: Injected<u32>
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use injectiny_proc_macro::injectable;
+
+#[derive(Clone)]
+enum Model {
+    Age(u32)
+}
+
+#[injectable(Model)]
+union Injectee {
+    age: u32
+}
+
+fn main() {}
@@ -1,11 +1,14 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
+use proc_macro2::Span;
 use std::fmt::Debug;
 
-use quote::{quote, ToTokens};
-use syn;
-use syn::{Attribute, Data, DeriveInput, Field, parenthesized, parse_macro_input, Path};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::{Attribute, Data, DeriveInput, Field, Fields, GenericArgument, Ident, Index, LitStr, Member, parse_macro_input, Path, PathArguments, Type};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::Token;
 
 struct EnumMember
 {
@@ -20,15 +23,20 @@ impl EnumMember
             a.ident == b.ident
         })
     }
+
+    fn name(&self) -> String
+    {
+        let segments = self.path.segments.iter().map(|segment| {
+            segment.ident.to_string()
+        }).collect::<Vec<_>>();
+        segments.join("::")
+    }
 }
 
 impl Debug for EnumMember
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let segments = self.path.segments.iter().map(|segment| {
-            segment.ident.to_string()
-        }).collect::<Vec<_>>();
-        segments.join("::").fmt(f)
+        self.name().fmt(f)
     }
 }
 
@@ -42,9 +50,7 @@ impl ToTokens for EnumMember
 impl syn::parse::Parse for EnumMember
 {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let content;
-        parenthesized!(content in input);
-        let path: Path = content.parse()?;
+        let path: Path = input.parse()?;
         let segments: Vec<_> = path.segments.iter().map(|segment| {
             segment.ident.to_string()
         }).collect();
@@ -57,11 +63,72 @@ impl syn::parse::Parse for EnumMember
     }
 }
 
+///
+/// The arguments of `#[inject(...)]`: an optional enum member, followed by options.
+///
+#[derive(Default)]
+struct InjectArgs
+{
+    member: Option<EnumMember>,
+    on_inject: Option<Ident>,
+    collect: bool
+}
+
+impl syn::parse::Parse for InjectArgs
+{
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+
+        while !input.is_empty() {
+            if input.peek(Ident) && input.peek2(Token![=]) {
+                let key: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                let value: LitStr = input.parse()?;
+
+                if key != "on_inject" {
+                    return Err(syn::Error::new_spanned(key, "Unknown #[inject] option, expected `on_inject = \"method\"`"));
+                }
+
+                if args.on_inject.is_some() {
+                    return Err(syn::Error::new_spanned(key, "`on_inject` can only be given once"));
+                }
+
+                args.on_inject = Some(value.parse()?);
+            }
+            else if input.fork().parse::<Ident>().is_ok_and(|ident| ident == "collect") && !input.peek2(Token![::]) {
+                let key: Ident = input.parse()?;
+
+                if args.collect {
+                    return Err(syn::Error::new_spanned(key, "`collect` can only be given once"));
+                }
+
+                args.collect = true;
+            }
+            else if args.member.is_none() && args.on_inject.is_none() && !args.collect {
+                args.member = Some(input.parse()?);
+            }
+            else {
+                return Err(input.error("Expected `collect` or `on_inject = \"method\"`"));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        if let (true, Some(on_inject)) = (args.collect, &args.on_inject) {
+            return Err(syn::Error::new_spanned(on_inject, "`on_inject` can't be combined with `collect`"));
+        }
+
+        Ok(args)
+    }
+}
+
 fn get_inject_attrib_index(field: &Field) -> Option<usize>
 {
     field.attrs.iter().position(|attr| {
         if let Some(ident) = attr.path.get_ident() {
-            return ident == "inject";
+            ident == "inject"
         }
         else {
             false
@@ -69,61 +136,922 @@ fn get_inject_attrib_index(field: &Field) -> Option<usize>
     })
 }
 
-fn parse_injected_fields(ast: &mut DeriveInput) -> Vec<(&Field, Attribute)> {
+///
+/// Returns the injected fields along with how they are accessed: by name, or by their position
+/// for tuple structs.
+///
+fn parse_injected_fields(ast: &mut DeriveInput) -> syn::Result<Vec<(Member, &Field, Attribute)>> {
     let mut fields = vec![];
 
-    if let Data::Struct(data) = &mut ast.data {
-        for field in data.fields.iter_mut() {
-            if let Some(i) = get_inject_attrib_index(field) {
-                let attrib = field.attrs.remove(i);
-                fields.push((&*field, attrib));
+    match &mut ast.data {
+        Data::Struct(data) => {
+            for (index, field) in data.fields.iter_mut().enumerate() {
+                if let Some(i) = get_inject_attrib_index(field) {
+                    let attrib = field.attrs.remove(i);
+                    let member = match &field.ident {
+                        Some(ident) => Member::Named(ident.clone()),
+                        None => Member::Unnamed(Index { index: index as u32, span: field.ty.span() })
+                    };
+
+                    fields.push((member, &*field, attrib));
+                }
             }
         }
+        Data::Enum(data) => {
+            return Err(syn::Error::new_spanned(data.enum_token, "#[injectable] can only be applied to structs"));
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(data.union_token, "#[injectable] can only be applied to structs"));
+        }
     }
-    else {
-        quote!(syn::Error::new_spanned(ast, "Injectable can only be applied to structs").to_compile_error().into());
+
+    Ok(fields)
+}
+
+fn push_error(errors: &mut Option<syn::Error>, error: syn::Error)
+{
+    match errors {
+        Some(errors) => errors.combine(error),
+        None => *errors = Some(error)
+    }
+}
+
+///
+/// Parses `#[inject(Enum::Member, on_inject = "method")]`. Without a member, as in a bare
+/// `#[inject]`, the member is left to be inferred from the field type.
+///
+fn parse_args(attrib: &Attribute) -> syn::Result<InjectArgs>
+{
+    if attrib.tokens.is_empty() {
+        return Ok(InjectArgs::default());
+    }
+
+    attrib.parse_args()
+}
+
+///
+/// Generates the statement assigning a newly injected value to a field. With an `on_inject` hook,
+/// the hook is called afterwards with the previous and the new value. With `collect`, the value is
+/// appended instead.
+///
+fn assign(field_name: &Member, value: proc_macro2::TokenStream, args: &InjectArgs) -> proc_macro2::TokenStream
+{
+    if args.collect {
+        return quote!(self.#field_name.get_or_insert_with(::std::vec::Vec::new).push(#value););
+    }
+
+    match &args.on_inject {
+        None => quote!(self.#field_name.set(#value);),
+        Some(hook) => quote! {
+            let new = #value;
+            let old = self.#field_name.take();
+            self.#field_name.set(::core::clone::Clone::clone(&new));
+            self.#hook(&old, &new);
+        }
+    }
+}
+
+///
+/// Returns `T` if the type is written as `Injected<T>`. Other spellings, such as aliases, are left
+/// for the compiler to infer.
+///
+fn injected_type(ty: &Type) -> Option<&Type>
+{
+    let Type::Path(path) = ty else { return None };
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "Injected" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first() {
+            Some(GenericArgument::Type(ty)) => Some(ty),
+            _ => None
+        },
+        _ => None
+    }
+}
+
+///
+/// Returns the type of the injected values: `T` for a field written as `Injected<T>`, or for a
+/// `collect` field, which must be written as `Injected<Vec<T>>`.
+///
+fn payload_type<'f>(field: &'f Field, args: &InjectArgs) -> syn::Result<Option<&'f Type>>
+{
+    if !args.collect {
+        return Ok(injected_type(&field.ty));
     }
 
-    fields
+    let element = injected_type(&field.ty).and_then(|ty| {
+        let Type::Path(path) = ty else { return None };
+        let segment = path.path.segments.last().filter(|segment| segment.ident == "Vec")?;
+
+        match &segment.arguments {
+            PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first() {
+                Some(GenericArgument::Type(ty)) => Some(ty),
+                _ => None
+            },
+            _ => None
+        }
+    });
+
+    match element {
+        Some(element) => Ok(Some(element)),
+        None => Err(syn::Error::new_spanned(&field.ty, "A field marked with `collect` must be of type `Injected<Vec<T>>`"))
+    }
+}
+
+///
+/// Generates the statement clearing a field if its value, wrapped in its enum member by `wrap`,
+/// matches. Values that don't match are unwrapped again by `unwrap` and put back.
+///
+fn uninject(
+    field_name: &Member,
+    wrap: proc_macro2::TokenStream,
+    unwrap: proc_macro2::TokenStream,
+    args: &InjectArgs
+) -> proc_macro2::TokenStream
+{
+    let check = quote! {
+        let model = #wrap;
+
+        let kept: ::core::option::Option<_> = if matches(&model) {
+            found = true;
+            ::core::option::Option::None
+        }
+        else {
+            #unwrap
+        };
+
+        if let ::core::option::Option::Some(value) = kept
+    };
+
+    if args.collect {
+        return quote! {
+            if let ::core::option::Option::Some(values) = self.#field_name.take() {
+                for value in values {
+                    #check {
+                        self.#field_name.get_or_insert_with(::std::vec::Vec::new).push(value);
+                    }
+                }
+            }
+        };
+    }
+
+    quote! {
+        if let ::core::option::Option::Some(value) = self.#field_name.take() {
+            #check {
+                self.#field_name.get_or_insert_with(|| value);
+            }
+        }
+    }
+}
+
+fn respan(tokens: proc_macro2::TokenStream, span: Span) -> proc_macro2::TokenStream
+{
+    tokens.into_iter().map(|mut token| {
+        if let proc_macro2::TokenTree::Group(group) = &token {
+            let mut inner = proc_macro2::Group::new(group.delimiter(), respan(group.stream(), span));
+            inner.set_span(span);
+            token = inner.into();
+        }
+        token.set_span(span);
+        token
+    }).collect()
+}
+
+fn injection_point(target: &Ident, field: &Member, variant: &proc_macro2::TokenStream) -> proc_macro2::TokenStream
+{
+    let target = target.to_string();
+    let field = match field {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => index.index.to_string()
+    };
+
+    quote! {
+        ::injectiny::InjectionPoint {
+            target: #target,
+            field: #field,
+            variant: #variant
+        }
+    }
 }
 
 #[proc_macro_attribute]
 pub fn injectable(attr: TokenStream, input: TokenStream) -> TokenStream {
-    let enum_val = parse_macro_input!(attr as Path);
+    let enums = parse_macro_input!(attr with Punctuated::<Path, Token![,]>::parse_terminated);
+    let enums: Vec<_> = enums.into_iter().collect();
     let mut ast = parse_macro_input!(input as DeriveInput);
-    let fields: Vec<_> = parse_injected_fields(&mut ast);
-    let mut matches = core::default::Default::default();
 
-    for (field, attrib) in fields.into_iter() {
-        let field_name = field.ident.as_ref().unwrap();
-        // TODO: Find enum member that matches the field type
+    match expand_injectable(&enums, &mut ast) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => {
+            let error = error.to_compile_error();
+            quote!(#ast #error).into()
+        }
+    }
+}
+
+///
+/// Generates the expressions converting a member's payload `value` into the field type, and back.
+/// Passing the payload through `Payload` makes a type mismatch point at the field.
+///
+fn payload_cast(field: &Field, field_type: impl ToTokens) -> (proc_macro2::TokenStream, proc_macro2::TokenStream)
+{
+    let cast = quote_spanned! { field.ty.span() =>
+        ::injectiny::__private::payload::<_, #field_type>()
+    };
 
-        let tokens = attrib.tokens.into();
-        let member = parse_macro_input!(tokens as EnumMember);
+    (quote!((#cast.into_field)(value)), quote!((#cast.from_field)(value)))
+}
 
+///
+/// Generates the expression taking the converted payload out of `model` if it is the given member.
+///
+fn extract_member(member: &EnumMember, value: &proc_macro2::TokenStream) -> proc_macro2::TokenStream
+{
+    quote! {
+        {
+            #[allow(unreachable_patterns)]
+            match model {
+                #member(value) => ::core::option::Option::Some(#value),
+                _ => ::core::option::Option::None
+            }
+        }
+    }
+}
+
+///
+/// Generates the `HasVariant` path that finds the member carrying the field type. Spanning the
+/// whole path at the field makes an inference failure point at it.
+///
+fn inferred_member(enum_val: &Path, field: &Field, field_type: impl ToTokens) -> proc_macro2::TokenStream
+{
+    respan(quote! {
+        <#enum_val as ::injectiny::HasVariant<#field_type>>
+    }, field.ty.span())
+}
 
-        if !member.has_enum_name(&enum_val) {
-            return quote!(syn::Error::new_spanned(ast, "All injected fields must be from the same enum").to_compile_error().into()).into();
+///
+/// Generates the match arm injecting a field from the model enum, the arm accepting its member, and
+/// the name of the member.
+///
+fn enum_injection(enum_val: &Path, field_name: &Member, field: &Field, args: InjectArgs) -> syn::Result<InjectionCode>
+{
+    let payload_type = payload_type(field, &args)?;
+
+    match &args.member {
+        Some(member) => {
+            let field_type = payload_type.map_or_else(|| quote!(_), |ty| quote!(#ty));
+            let (value, payload) = payload_cast(field, field_type);
+            let variant = member.name();
+            let assign = assign(field_name, value.clone(), &args);
+            let unwrap = extract_member(member, &value);
+
+            Ok(InjectionCode {
+                inject: quote!(#member(value) => { #assign }),
+                accept: quote!(#member(_) => true,),
+                uninject: uninject(field_name, quote!(#member(#payload)), unwrap, &args),
+                variant: quote!(#variant)
+            })
+        }
+        None => {
+            let Some(field_type) = payload_type else {
+                let error = "Cannot infer the enum member: the field type must be written as `Injected<T>`, or the member must be named with `#[inject(Enum::Member)]`";
+                return Err(syn::Error::new_spanned(&field.ty, error));
+            };
+            let variant = inferred_member(enum_val, field, field_type);
+            let assign = assign(field_name, quote!(value), &args);
+            let wrap = quote!(#variant::from_payload(value));
+            let unwrap = quote!(#variant::into_payload(model).ok());
+
+            Ok(InjectionCode {
+                inject: quote! {
+                    model if #variant::has_payload(&model) => {
+                        if let Ok(value) = #variant::into_payload(model) {
+                            #assign
+                        }
+                    }
+                },
+                accept: quote!(model if #variant::has_payload(model) => true,),
+                uninject: uninject(field_name, wrap, unwrap, &args),
+                variant: quote!(#variant::VARIANT)
+            })
         }
+    }
+}
+
+///
+/// Generates the statement injecting a field from a type-keyed Binding, the expression accepting
+/// it, and the name of its type.
+///
+fn type_injection(field_name: &Member, field: &Field, args: InjectArgs) -> syn::Result<InjectionCode>
+{
+    if let Some(member) = &args.member {
+        let error = "Enum members can only be injected with a model enum: `#[injectable(Model)]`";
+        return Err(syn::Error::new_spanned(member, error));
+    }
 
-        matches = quote! {
-            #matches
-            #member(value) => self.#field_name = injectiny::Injected::from(value),
+    let Some(field_type) = payload_type(field, &args)? else {
+        let error = "Without a model enum, the field type must be written as `Injected<T>`";
+        return Err(syn::Error::new_spanned(&field.ty, error));
+    };
+
+    let assign = assign(field_name, quote!(value), &args);
+    let wrap = quote!(::injectiny::Binding::new(value));
+    let unwrap = quote!(model.get::<#field_type>());
+
+    Ok(InjectionCode {
+        inject: quote! {
+            if let Some(value) = model.get::<#field_type>() {
+                #assign
+            }
+        },
+        accept: quote!(|| model.is::<#field_type>()),
+        uninject: uninject(field_name, wrap, unwrap, &args),
+        variant: quote!(::core::any::type_name::<#field_type>())
+    })
+}
+
+struct InjectionCode
+{
+    inject: proc_macro2::TokenStream,
+    accept: proc_macro2::TokenStream,
+    uninject: proc_macro2::TokenStream,
+    variant: proc_macro2::TokenStream
+}
+
+///
+/// The code of one `Injectable` implementation, collected from the fields injected from its model.
+///
+#[derive(Default)]
+struct ModelCode
+{
+    matches: proc_macro2::TokenStream,
+    descriptions: proc_macro2::TokenStream,
+    missing: proc_macro2::TokenStream,
+    injected: proc_macro2::TokenStream,
+    accepted: proc_macro2::TokenStream,
+    uninjected: proc_macro2::TokenStream
+}
+
+///
+/// Picks the model enum a field is injected from: the enum of its member, or the only enum if the
+/// member is inferred.
+///
+fn field_model(enums: &[Path], attrib: &Attribute, args: &InjectArgs) -> syn::Result<usize>
+{
+    match (&args.member, enums.len()) {
+        (Some(member), 1) if !member.has_enum_name(&enums[0]) => {
+            Err(syn::Error::new_spanned(member, "All injected fields must be from the same enum"))
+        }
+        (Some(member), _) => enums.iter().position(|enum_val| member.has_enum_name(enum_val)).ok_or_else(|| {
+            syn::Error::new_spanned(member, "Injected fields must be from one of the enums given to #[injectable]")
+        }),
+        (None, 1) => Ok(0),
+        (None, _) => {
+            let error = "With multiple model enums, the member must be named: `#[inject(Enum::Member)]`";
+            Err(syn::Error::new_spanned(attrib, error))
+        }
+    }
+}
+
+fn expand_injectable(enums: &[Path], ast: &mut DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = ast.ident.clone();
+    let fields: Vec<_> = parse_injected_fields(ast)?;
+    let mut errors = None;
+    // Without a model enum, all fields are injected from a Binding
+    let mut models: Vec<ModelCode> = (0..enums.len().max(1)).map(|_| ModelCode::default()).collect();
+
+    for (field_name, field, attrib) in fields.iter() {
+        let code = parse_args(attrib).and_then(|args| {
+            if enums.is_empty() {
+                return Ok((0, type_injection(field_name, field, args)?));
+            }
+
+            let index = field_model(enums, attrib, &args)?;
+            Ok((index, enum_injection(&enums[index], field_name, field, args)?))
+        });
+
+        let (index, InjectionCode { inject, accept, uninject, variant }) = match code {
+            Ok(code) => code,
+            Err(error) => {
+                push_error(&mut errors, error);
+                continue;
+            }
         };
+
+        let point = injection_point(&name, field_name, &variant);
+        let model = &mut models[index];
+
+        model.matches.extend(inject);
+
+        model.descriptions.extend(quote! {
+            self.#field_name.describe(#point);
+        });
+
+        model.missing.extend(quote! {
+            if !self.#field_name.is_injected() {
+                missing.push(#point);
+            }
+        });
+
+        model.injected.extend(quote!(&& self.#field_name.is_injected()));
+
+        model.accepted.extend(accept);
+
+        model.uninjected.extend(uninject);
     }
 
-    let name = &ast.ident;
-    let quote = quote! {
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    let impls = models.into_iter().enumerate().map(|(index, code)| {
+        impl_injectable(&name, &ast.generics, enums.get(index), code)
+    });
+
+    Ok(quote! {
         #ast
+        #(#impls)*
+    })
+}
 
-        impl ::injectiny::Injectable<#enum_val> for #name {
-            fn inject(&mut self, model: #enum_val) {
+fn impl_injectable(name: &Ident, generics: &syn::Generics, enum_val: Option<&Path>, code: ModelCode) -> proc_macro2::TokenStream
+{
+    let ModelCode { matches, descriptions, missing, injected, accepted, uninjected } = code;
+
+    let (model_type, inject, accept) = match enum_val {
+        Some(enum_val) => (
+            quote!(#enum_val),
+            quote! {
+                #[allow(unreachable_patterns)]
                 match model {
                     #matches
                     _ => {}
                 }
+            },
+            quote! {
+                #[allow(unreachable_patterns)]
+                match model {
+                    #accepted
+                    _ => false
+                }
+            }
+        ),
+        None => (
+            quote!(::injectiny::Binding),
+            quote! {
+                let _ = &model;
+                #matches
+            },
+            quote!(false #accepted)
+        )
+    };
+
+    // A generic model is only clonable for some of its arguments, so the impl is limited to those
+    let mut generics = generics.clone();
+
+    if enum_val.is_some() && !generics.params.is_empty() {
+        generics.make_where_clause().predicates.push(syn::parse_quote!(#model_type: ::core::clone::Clone));
+    }
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::injectiny::Injectable<#model_type> for #name #type_generics #where_clause {
+            fn inject(&mut self, model: #model_type) {
+                #inject
+            }
+
+            fn missing_injections(&self) -> ::std::vec::Vec<::injectiny::InjectionPoint> {
+                #[allow(unused_mut)]
+                let mut missing = ::std::vec::Vec::new();
+                #missing
+                missing
+            }
+
+            fn is_fully_injected(&self) -> bool {
+                true #injected
+            }
+
+            fn describe_fields(&mut self) {
+                #descriptions
+            }
+
+            fn accepts(&self, model: &#model_type) -> bool {
+                let _ = model;
+                #accept
+            }
+
+            fn uninject_matching(&mut self, matches: &mut dyn ::core::ops::FnMut(&#model_type) -> bool) -> bool {
+                let _ = matches;
+                #[allow(unused_mut)]
+                let mut found = false;
+                #uninjected
+                found
+            }
+        }
+    }
+}
+
+#[proc_macro_attribute]
+pub fn model(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    let result = if attr.is_empty() {
+        expand_model(&ast)
+    }
+    else {
+        let attr = proc_macro2::TokenStream::from(attr);
+        Err(syn::Error::new_spanned(attr, "#[model] does not take any arguments"))
+    };
+
+    match result {
+        Ok(tokens) => tokens.into(),
+        Err(error) => {
+            let error = error.to_compile_error();
+            quote!(#ast #error).into()
+        }
+    }
+}
+
+///
+/// Generates a check that the model is `Clone`, for every clonable type argument. Derives written
+/// above `#[model]` aren't visible to it, so it can't derive `Clone` itself.
+///
+fn assert_clone(ast: &DeriveInput) -> proc_macro2::TokenStream
+{
+    let name = &ast.ident;
+    let mut generics = ast.generics.clone();
+
+    for param in generics.type_params_mut() {
+        param.bounds.push(syn::parse_quote!(::core::clone::Clone));
+    }
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    quote_spanned! { name.span() =>
+        const _: () = {
+            #[allow(dead_code)]
+            fn assert_clone<T: ::core::clone::Clone>() {}
+
+            #[allow(dead_code)]
+            fn assert_model #impl_generics () #where_clause {
+                assert_clone::<#name #type_generics>();
+            }
+        };
+    }
+}
+
+fn is_type_param(ty: &Type, ast: &DeriveInput) -> bool
+{
+    let Type::Path(path) = ty else { return false };
+
+    ast.generics.type_params().any(|param| path.qself.is_none() && path.path.is_ident(&param.ident))
+}
+
+///
+/// Returns a key that identifies a payload type when grouping members. Paths are reduced to their
+/// last segment, so `String` and `std::string::String` are grouped together. Types that only differ
+/// in their module, such as `fmt::Error` and `io::Error`, are grouped as well, which leaves both
+/// without helpers rather than generating conflicting ones. Type aliases can't be seen through.
+///
+fn type_key(ty: &Type) -> String
+{
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            let Some(segment) = path.path.segments.last() else { return String::new() };
+
+            match &segment.arguments {
+                PathArguments::None => segment.ident.to_string(),
+                PathArguments::AngleBracketed(args) => {
+                    let args: Vec<_> = args.args.iter().map(|arg| match arg {
+                        GenericArgument::Type(ty) => type_key(ty),
+                        arg => arg.to_token_stream().to_string()
+                    }).collect();
+                    format!("{}<{}>", segment.ident, args.join(", "))
+                }
+                PathArguments::Parenthesized(args) => format!("{}{}", segment.ident, args.to_token_stream())
             }
         }
+        Type::Reference(reference) => {
+            let lifetime = reference.lifetime.as_ref().map_or_else(String::new, |lifetime| format!("{} ", lifetime));
+            let mutability = if reference.mutability.is_some() { "mut " } else { "" };
+            format!("&{}{}{}", lifetime, mutability, type_key(&reference.elem))
+        }
+        Type::Tuple(tuple) => {
+            let elems: Vec<_> = tuple.elems.iter().map(type_key).collect();
+            format!("({})", elems.join(", "))
+        }
+        Type::Slice(slice) => format!("[{}]", type_key(&slice.elem)),
+        Type::Array(array) => format!("[{}; {}]", type_key(&array.elem), array.len.to_token_stream()),
+        Type::Paren(paren) => type_key(&paren.elem),
+        Type::Group(group) => type_key(&group.elem),
+        ty => ty.to_token_stream().to_string()
+    }
+}
+
+fn expand_model(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let data = match &ast.data {
+        Data::Enum(data) => data,
+        Data::Struct(data) => {
+            return Err(syn::Error::new_spanned(data.struct_token, "#[model] can only be applied to enums"));
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(data.union_token, "#[model] can only be applied to enums"));
+        }
     };
-    quote.into()
+
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let key_name = format_ident!("{}Key", name);
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+    let key_variants: Vec<_> = data.variants.iter().map(|variant| &variant.ident).collect();
+    let key_names = key_variants.iter().map(|variant| format!("{}::{}", name, variant));
+    let key_doc = format!("Identifies the members of [`{}`] without their payloads.", name);
+    let assert_clone = assert_clone(ast);
+
+    // Group the members by payload type, so only types carried by a single member are mapped
+    let mut payloads: Vec<(String, &Type, Vec<&Ident>)> = vec![];
+
+    for variant in &data.variants {
+        let Fields::Unnamed(fields) = &variant.fields else { continue };
+
+        if fields.unnamed.len() != 1 {
+            continue;
+        }
+
+        let ty = &fields.unnamed[0].ty;
+        let key = type_key(ty);
+
+        match payloads.iter_mut().find(|(other, _, _)| *other == key) {
+            Some((_, _, variants)) => variants.push(&variant.ident),
+            None => payloads.push((key, ty, vec![&variant.ident]))
+        }
+    }
+
+    let mut impls = proc_macro2::TokenStream::new();
+
+    for (_, ty, variants) in payloads {
+        let [variant] = variants[..] else { continue };
+        let variant_name = format!("{}::{}", name, variant);
+        // Payloads that are the same type under another name can't be grouped, such as type
+        // aliases. Spanning the impls at the payload makes the conflict point at the member.
+        let span = ty.span();
+
+        impls = quote_spanned! { span =>
+            #impls
+
+            impl #impl_generics ::injectiny::HasVariant<#ty> for #name #type_generics #where_clause {
+                const VARIANT: &'static str = #variant_name;
+                const KEY: #key_name = #key_name::#variant;
+
+                fn from_payload(payload: #ty) -> Self {
+                    Self::#variant(payload)
+                }
+
+                fn into_payload(self) -> ::core::result::Result<#ty, Self> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        Self::#variant(payload) => ::core::result::Result::Ok(payload),
+                        model => ::core::result::Result::Err(model)
+                    }
+                }
+
+                fn has_payload(&self) -> bool {
+                    ::core::matches!(self, Self::#variant(_))
+                }
+            }
+        };
+
+        // These would overlap with the standard library's blanket impls for a bare type parameter
+        if is_type_param(ty, ast) {
+            continue;
+        }
+
+        impls = quote_spanned! { span =>
+            #impls
+
+            impl #impl_generics ::core::convert::From<#ty> for #name #type_generics #where_clause {
+                fn from(payload: #ty) -> Self {
+                    Self::#variant(payload)
+                }
+            }
+
+            impl #impl_generics ::core::convert::TryFrom<#name #type_generics> for #ty #where_clause {
+                type Error = #name #type_generics;
+
+                fn try_from(model: #name #type_generics) -> ::core::result::Result<Self, Self::Error> {
+                    <#name #type_generics as ::injectiny::HasVariant<#ty>>::into_payload(model)
+                }
+            }
+        };
+    }
+
+    Ok(quote! {
+        #ast
+        #assert_clone
+
+        #[doc = #key_doc]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis enum #key_name {
+            #(#key_variants),*
+        }
+
+        impl #key_name {
+            ///
+            /// Returns the name of the member, as used by `InjectionPoint::variant`.
+            ///
+            #vis fn name(&self) -> &'static str {
+                match *self {
+                    #(#key_name::#key_variants => #key_names),*
+                }
+            }
+        }
+
+        impl #impl_generics #name #type_generics #where_clause {
+            ///
+            /// Returns the key of this value's member.
+            ///
+            #vis fn key(&self) -> #key_name {
+                match *self {
+                    #(Self::#key_variants { .. } => #key_name::#key_variants),*
+                }
+            }
+        }
+
+        impl #impl_generics ::injectiny::Keyed for #name #type_generics #where_clause {
+            type Key = #key_name;
+
+            fn key(&self) -> #key_name {
+                #name::key(self)
+            }
+        }
+
+        #impls
+    })
+}
+
+#[proc_macro_derive(Construct, attributes(construct, inject))]
+pub fn construct(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    match expand_construct(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into()
+    }
+}
+
+///
+/// Generates the closure extracting a field's value from the model enum, along with the name and
+/// the key of the member it comes from.
+///
+fn construct_extraction(enum_val: &Path, field: &Field, args: InjectArgs) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream, proc_macro2::TokenStream)>
+{
+    if let Some(on_inject) = args.on_inject {
+        return Err(syn::Error::new_spanned(on_inject, "`on_inject` is not supported by #[derive(Construct)]"));
+    }
+
+    if args.collect {
+        return Err(syn::Error::new_spanned(&field.ty, "`collect` is not supported by #[derive(Construct)]"));
+    }
+
+    let field_type = &field.ty;
+
+    match args.member {
+        Some(member) if !member.has_enum_name(enum_val) => {
+            Err(syn::Error::new_spanned(member, "All injected fields must be from the same enum"))
+        }
+        Some(member) => {
+            let (value, _) = payload_cast(field, field_type);
+            let extract = extract_member(&member, &value);
+            let variant = member.name();
+            let ident = &member.path.segments.last().expect("member paths have two segments").ident;
+
+            Ok((
+                quote!(|model| #extract),
+                quote!(#variant),
+                quote!(<<#enum_val as ::injectiny::Keyed>::Key>::#ident)
+            ))
+        }
+        None => {
+            let variant = inferred_member(enum_val, field, field_type);
+
+            Ok((
+                quote!(|model| #variant::into_payload(model).ok()),
+                quote!(#variant::VARIANT),
+                quote!(#variant::KEY)
+            ))
+        }
+    }
+}
+
+fn expand_construct(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let fields = match &ast.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            return Err(syn::Error::new_spanned(data.enum_token, "#[derive(Construct)] can only be applied to structs"));
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(data.union_token, "#[derive(Construct)] can only be applied to structs"));
+        }
+    };
+
+    let Some(attrib) = ast.attrs.iter().find(|attr| attr.path.is_ident("construct")) else {
+        return Err(syn::Error::new(Span::call_site(), "#[derive(Construct)] requires the model enum: `#[construct(Model)]`"));
+    };
+    let enum_val: Path = attrib.parse_args()?;
+
+    let mut errors = None;
+    let mut resolved = proc_macro2::TokenStream::new();
+    let mut missing = proc_macro2::TokenStream::new();
+    let mut injected = vec![];
+    let mut initializers = vec![];
+
+    for (position, field) in fields.iter().enumerate() {
+        // Fields are resolved into private locals, so they can't clash with the generated names
+        let field_name = format_ident!("__injectiny_field_{}", position);
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(position))
+        };
+
+        let Some(index) = get_inject_attrib_index(field) else {
+            initializers.push(quote!(#member: ::core::default::Default::default()));
+            continue;
+        };
+
+        let (extract, variant, key) = match parse_args(&field.attrs[index]).and_then(|args| construct_extraction(&enum_val, field, args)) {
+            Ok(code) => code,
+            Err(error) => {
+                push_error(&mut errors, error);
+                continue;
+            }
+        };
+
+        let point = injection_point(name, &member, &variant);
+
+        resolved = quote! {
+            #resolved
+            let #field_name = match resolver.resolve(#key, #extract) {
+                ::core::result::Result::Ok(value) => value,
+                ::core::result::Result::Err(error) => {
+                    failures.push((#point, error));
+                    ::core::option::Option::None
+                }
+            };
+        };
+
+        missing = quote! {
+            #missing
+            if #field_name.is_none() {
+                missing.push(#point);
+            }
+        };
+
+        initializers.push(quote!(#member: #field_name));
+        injected.push(field_name);
+    }
+
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    let (declare, unwrap) = if injected.is_empty() {
+        (quote!(), quote!())
+    }
+    else {
+        (quote!(let mut failures = ::std::vec::Vec::new();), quote! {
+            let mut missing = ::std::vec::Vec::new();
+            #missing
+
+            let (#(::core::option::Option::Some(#injected),)*) = (#(#injected,)*) else {
+                return ::core::result::Result::Err(::injectiny::Missing::new(missing, failures));
+            };
+        })
+    };
+
+    Ok(quote! {
+        impl #impl_generics #name #type_generics #where_clause {
+            ///
+            /// Builds this struct from the values resolved from the model, or reports every field
+            /// that could not be resolved.
+            ///
+            #vis fn construct<R: ::injectiny::Resolve<#enum_val>>(resolver: &R) -> ::core::result::Result<Self, ::injectiny::Missing> {
+                let _ = resolver;
+                #declare
+                #resolved
+                #unwrap
+                ::core::result::Result::Ok(Self {
+                    #(#initializers),*
+                })
+            }
+        }
+    })
 }